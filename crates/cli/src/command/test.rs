@@ -3,7 +3,13 @@ use fehler::throws;
 use trdelnik_client::*;
 
 #[throws]
-pub async fn test(root: String) {
+pub async fn test(root: String, check_cu: bool, cu_threshold: f64, update_cu_baseline: bool) {
     let commander = Commander::with_root(root);
-    commander.run_tests().await?;
+    if check_cu || update_cu_baseline {
+        commander
+            .run_tests_with_cu_check(cu_threshold, update_cu_baseline)
+            .await?;
+    } else {
+        commander.run_tests().await?;
+    }
 }
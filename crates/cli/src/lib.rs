@@ -34,6 +34,15 @@ enum Command {
         /// Anchor project root
         #[clap(short, long, default_value = "./")]
         root: String,
+        /// Compare the consumed compute units with `trdelnik-tests/cu-baseline.json`
+        #[clap(long)]
+        check_cu: bool,
+        /// Allowed increase of the consumed compute units in percent
+        #[clap(long, default_value_t = 5.0)]
+        cu_threshold: f64,
+        /// Record the consumed compute units to `trdelnik-tests/cu-baseline.json`
+        #[clap(long)]
+        update_cu_baseline: bool,
     },
     /// Run and debug fuzz tests
     Fuzz {
@@ -65,7 +74,12 @@ pub async fn start() {
     match cli.command {
         Command::Build { root } => command::build(root).await?,
         Command::KeyPair { subcmd } => command::keypair(subcmd)?,
-        Command::Test { root } => command::test(root).await?,
+        Command::Test {
+            root,
+            check_cu,
+            cu_threshold,
+            update_cu_baseline,
+        } => command::test(root, check_cu, cu_threshold, update_cu_baseline).await?,
         Command::Fuzz { root, subcmd } => command::fuzz(root, subcmd).await?,
         Command::Localnet => command::localnet().await?,
         Command::Explorer { subcmd } => command::explorer(subcmd).await?,
@@ -0,0 +1,560 @@
+use crate::PrintableTransaction;
+use anchor_client::solana_sdk::{account::Account, clock::Slot, hash::hash, pubkey::Pubkey};
+use anchor_syn::idl::{EnumFields, Idl, IdlField, IdlType, IdlTypeDefinitionTy};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The state of the accounts at a slot, taken by [Client::snapshot](crate::Client::snapshot).
+///
+/// Compare two snapshots with [AccountSnapshot::diff] to see what the transactions in between changed.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let before = client.snapshot(&[escrow.pubkey(), alice_token_a_account]).await?;
+/// escrow_instruction::initialize_escrow(&client, /* ... */).await?;
+/// let after = client.snapshot(&[escrow.pubkey(), alice_token_a_account]).await?;
+/// let idl = serde_json::from_str(include_str!("../../target/idl/escrow.json"))?;
+/// before.diff(&after).with_idl_fields(&idl).print();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountSnapshot {
+    pub slot: Slot,
+    pub accounts: Vec<SnapshotAccount>,
+}
+
+/// The snapshotted account.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotAccount {
+    #[serde(with = "pubkey_string")]
+    pub address: Pubkey,
+    /// `None` when the account does not exist.
+    pub state: Option<AccountState>,
+}
+
+/// The lamports, owner and data of the snapshotted account.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountState {
+    pub lamports: u64,
+    #[serde(with = "pubkey_string")]
+    pub owner: Pubkey,
+    #[serde(with = "base64_data")]
+    pub data: Vec<u8>,
+}
+
+impl From<Account> for AccountState {
+    fn from(account: Account) -> Self {
+        Self {
+            lamports: account.lamports,
+            owner: account.owner,
+            data: account.data,
+        }
+    }
+}
+
+impl AccountSnapshot {
+    pub(crate) fn new(slot: Slot, accounts: Vec<(Pubkey, Option<Account>)>) -> Self {
+        Self {
+            slot,
+            accounts: accounts
+                .into_iter()
+                .map(|(address, account)| SnapshotAccount {
+                    address,
+                    state: account.map(AccountState::from),
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the state of the account, `None` when it was not snapshotted or did not exist.
+    pub fn state(&self, address: &Pubkey) -> Option<&AccountState> {
+        self.accounts
+            .iter()
+            .find(|account| &account.address == address)
+            .and_then(|account| account.state.as_ref())
+    }
+
+    /// Compares this snapshot with the `after` snapshot.
+    ///
+    /// Only the changed accounts are reported, in the order of this snapshot
+    /// followed by the accounts snapshotted only `after`.
+    pub fn diff(&self, after: &AccountSnapshot) -> SnapshotDiff {
+        let mut addresses = self
+            .accounts
+            .iter()
+            .map(|account| account.address)
+            .collect::<Vec<_>>();
+        for account in &after.accounts {
+            if !addresses.contains(&account.address) {
+                addresses.push(account.address);
+            }
+        }
+        SnapshotDiff {
+            slots: (self.slot, after.slot),
+            accounts: addresses
+                .into_iter()
+                .filter_map(|address| {
+                    AccountDiff::new(address, self.state(&address), after.state(&address))
+                })
+                .collect(),
+        }
+    }
+}
+
+/// The changes of the accounts between two snapshots, see [AccountSnapshot::diff].
+///
+/// It is printed like [PrintableTransaction] and serialized to JSON with `serde_json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SnapshotDiff {
+    /// The slots of the compared snapshots.
+    pub slots: (Slot, Slot),
+    /// The changed accounts.
+    pub accounts: Vec<AccountDiff>,
+}
+
+impl SnapshotDiff {
+    /// Returns `true` when no account has changed.
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+
+    /// Returns the changes of the account, `None` when it has not changed.
+    pub fn account(&self, address: &Pubkey) -> Option<&AccountDiff> {
+        self.accounts
+            .iter()
+            .find(|account| &account.address == address)
+    }
+
+    /// Decodes the data of the changed Anchor accounts defined in the program `idl`
+    /// (e.g. `target/idl/<program>.json`) and reports the changes of their fields, see [AccountDiff::fields].
+    ///
+    /// The nested fields are reported separately, e.g. `config.fee`, `holders[0]` or `state.since`;
+    /// an enum field holds the name of its variant. The accounts which don't decode are left
+    /// with the byte-level changes only.
+    pub fn with_idl_fields(mut self, idl: &Idl) -> Self {
+        for account in &mut self.accounts {
+            let decode = |data: &Option<Vec<u8>>| {
+                data.as_deref()
+                    .and_then(|data| IdlDecoder::decode_account(idl, data))
+            };
+            let (before, after) = (decode(&account.data.0), decode(&account.data.1));
+            let account_type = match (&before, &after) {
+                (_, Some((account_type, _))) | (Some((account_type, _)), None) => {
+                    account_type.clone()
+                }
+                (None, None) => continue,
+            };
+            let (before, after) = (
+                before.map(|(_, fields)| fields).unwrap_or_default(),
+                after.map(|(_, fields)| fields).unwrap_or_default(),
+            );
+            let value = |fields: &[(String, String)], name: &str| {
+                fields
+                    .iter()
+                    .find(|(field, _)| field == name)
+                    .map(|(_, value)| value.clone())
+            };
+            account.fields = field_names(&before, &after)
+                .into_iter()
+                .filter_map(|name| {
+                    let (before, after) = (value(&before, &name), value(&after, &name));
+                    (before != after).then(|| FieldChange {
+                        account_type: account_type.clone(),
+                        name,
+                        before,
+                        after,
+                    })
+                })
+                .collect();
+        }
+        self
+    }
+}
+
+/// The changes of an account between two snapshots.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AccountDiff {
+    #[serde(with = "pubkey_string")]
+    pub address: Pubkey,
+    pub status: AccountStatus,
+    /// The change of the lamports; the missing accounts have `0` lamports.
+    pub lamports_delta: i128,
+    /// The owner change, if any.
+    pub owner: Option<Change<String>>,
+    /// The data length change, if any.
+    pub data_len: Option<Change<usize>>,
+    /// The changed byte ranges of the data.
+    pub bytes: Vec<ByteChange>,
+    /// The changed fields of the Anchor account, see [SnapshotDiff::with_idl_fields].
+    pub fields: Vec<FieldChange>,
+    #[serde(skip)]
+    data: (Option<Vec<u8>>, Option<Vec<u8>>),
+}
+
+impl AccountDiff {
+    fn new(
+        address: Pubkey,
+        before: Option<&AccountState>,
+        after: Option<&AccountState>,
+    ) -> Option<Self> {
+        if before == after {
+            return None;
+        }
+        let status = match (before, after) {
+            (None, Some(_)) => AccountStatus::Created,
+            (Some(_), None) => AccountStatus::Closed,
+            _ => AccountStatus::Modified,
+        };
+        let lamports = |state: Option<&AccountState>| state.map_or(0, |state| state.lamports);
+        let owner =
+            (before.map(|state| state.owner) != after.map(|state| state.owner)).then(|| Change {
+                before: before.map(|state| state.owner.to_string()),
+                after: after.map(|state| state.owner.to_string()),
+            });
+        let data = |state: Option<&AccountState>| state.map(|state| state.data.clone());
+        let (before_data, after_data) = (data(before), data(after));
+        let (before_bytes, after_bytes) = (
+            before_data.as_deref().unwrap_or_default(),
+            after_data.as_deref().unwrap_or_default(),
+        );
+        let data_len = (before_bytes.len() != after_bytes.len()).then(|| Change {
+            before: before.map(|state| state.data.len()),
+            after: after.map(|state| state.data.len()),
+        });
+        Some(Self {
+            address,
+            status,
+            lamports_delta: lamports(after) as i128 - lamports(before) as i128,
+            owner,
+            data_len,
+            bytes: byte_changes(before_bytes, after_bytes),
+            fields: Vec::new(),
+            data: (before_data, after_data),
+        })
+    }
+}
+
+/// Whether the account was created, closed or modified between the snapshots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountStatus {
+    Created,
+    Closed,
+    Modified,
+}
+
+/// A value before and after, `None` when the account or field did not exist.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Change<T> {
+    pub before: Option<T>,
+    pub after: Option<T>,
+}
+
+/// A changed range of the account data.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ByteChange {
+    pub offset: usize,
+    /// The bytes of the range before; shorter when the account was resized.
+    #[serde(serialize_with = "serialize_hex")]
+    pub before: Vec<u8>,
+    /// The bytes of the range after; shorter when the account was resized.
+    #[serde(serialize_with = "serialize_hex")]
+    pub after: Vec<u8>,
+}
+
+/// A changed field of an Anchor account.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldChange {
+    pub account_type: String,
+    pub name: String,
+    /// The decoded value of the field before.
+    pub before: Option<String>,
+    /// The decoded value of the field after.
+    pub after: Option<String>,
+}
+
+impl PrintableTransaction for SnapshotDiff {
+    fn print_named(&self, name: &str) {
+        println!(
+            "ACCOUNT CHANGES {} (slots {}..{})",
+            name, self.slots.0, self.slots.1
+        );
+        for line in self.to_string().lines() {
+            println!("  {line}");
+        }
+    }
+}
+
+impl fmt::Display for SnapshotDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.accounts.is_empty() {
+            return writeln!(f, "No account changes");
+        }
+        let option = |value: &Option<String>| value.as_deref().unwrap_or("-").to_string();
+        for account in &self.accounts {
+            writeln!(f, "Account {} ({:?}):", account.address, account.status)?;
+            if account.lamports_delta != 0 {
+                writeln!(f, "  Lamports: {:+}", account.lamports_delta)?;
+            }
+            if let Some(owner) = &account.owner {
+                writeln!(
+                    f,
+                    "  Owner: {} -> {}",
+                    option(&owner.before),
+                    option(&owner.after)
+                )?;
+            }
+            if let Some(data_len) = &account.data_len {
+                let len = |len: Option<usize>| len.map_or("-".to_string(), |len| len.to_string());
+                writeln!(
+                    f,
+                    "  Data length: {} -> {}",
+                    len(data_len.before),
+                    len(data_len.after)
+                )?;
+            }
+            if account.fields.is_empty() {
+                let bytes = |bytes: &[u8]| match bytes.is_empty() {
+                    true => "-".to_string(),
+                    false => hex(bytes),
+                };
+                for change in &account.bytes {
+                    writeln!(
+                        f,
+                        "  Bytes {}..{}: {} -> {}",
+                        change.offset,
+                        change.offset + change.before.len().max(change.after.len()),
+                        bytes(&change.before),
+                        bytes(&change.after)
+                    )?;
+                }
+            }
+            for field in &account.fields {
+                writeln!(
+                    f,
+                    "  Field {}: {} -> {}",
+                    field.name,
+                    option(&field.before),
+                    option(&field.after)
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Groups the differing bytes into continuous ranges.
+fn byte_changes(before: &[u8], after: &[u8]) -> Vec<ByteChange> {
+    let mut changes = Vec::new();
+    let mut start = None;
+    for offset in 0..=before.len().max(after.len()) {
+        let differs =
+            offset < before.len().max(after.len()) && before.get(offset) != after.get(offset);
+        match (differs, start) {
+            (true, None) => start = Some(offset),
+            (false, Some(range_start)) => {
+                let range = |data: &[u8]| {
+                    data.get(range_start..offset.min(data.len()))
+                        .unwrap_or_default()
+                        .to_vec()
+                };
+                changes.push(ByteChange {
+                    offset: range_start,
+                    before: range(before),
+                    after: range(after),
+                });
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    changes
+}
+
+/// Merges the field names of both decoded values, the fields present only `after`
+/// follow their preceding field.
+fn field_names(before: &[(String, String)], after: &[(String, String)]) -> Vec<String> {
+    let mut names = before
+        .iter()
+        .map(|(name, _)| name.clone())
+        .collect::<Vec<_>>();
+    let mut position = 0;
+    for (name, _) in after {
+        match names.iter().position(|known| known == name) {
+            Some(index) => position = index + 1,
+            None => {
+                names.insert(position, name.clone());
+                position += 1;
+            }
+        }
+    }
+    names
+}
+
+/// Decodes the Borsh serialized Anchor accounts into the flat list of their fields
+/// according to the types in the IDL.
+struct IdlDecoder<'a> {
+    idl: &'a Idl,
+    fields: Vec<(String, String)>,
+}
+
+impl<'a> IdlDecoder<'a> {
+    /// Returns the account type name and its fields when the data start with
+    /// the discriminator of an IDL account and decode completely.
+    fn decode_account(idl: &'a Idl, data: &[u8]) -> Option<(String, Vec<(String, String)>)> {
+        let account = idl.accounts.iter().find(|account| {
+            data.starts_with(&hash(format!("account:{}", account.name).as_bytes()).to_bytes()[..8])
+        })?;
+        let mut decoder = Self {
+            idl,
+            fields: Vec::new(),
+        };
+        decoder.decode_type_definition(String::new(), &account.ty, &mut &data[8..])?;
+        Some((account.name.clone(), decoder.fields))
+    }
+
+    fn decode_type_definition(
+        &mut self,
+        path: String,
+        ty: &IdlTypeDefinitionTy,
+        data: &mut &[u8],
+    ) -> Option<()> {
+        match ty {
+            IdlTypeDefinitionTy::Struct { fields } => self.decode_fields(&path, fields, data),
+            IdlTypeDefinitionTy::Enum { variants } => {
+                let variant = variants.get(usize::from(take::<1>(data)?[0]))?;
+                self.fields.push((path.clone(), variant.name.clone()));
+                match &variant.fields {
+                    Some(EnumFields::Named(fields)) => self.decode_fields(&path, fields, data),
+                    Some(EnumFields::Tuple(types)) => {
+                        types.iter().enumerate().try_for_each(|(index, ty)| {
+                            self.decode(field_path(&path, &index.to_string()), ty, data)
+                        })
+                    }
+                    None => Some(()),
+                }
+            }
+        }
+    }
+
+    fn decode_fields(&mut self, path: &str, fields: &[IdlField], data: &mut &[u8]) -> Option<()> {
+        fields
+            .iter()
+            .try_for_each(|field| self.decode(field_path(path, &field.name), &field.ty, data))
+    }
+
+    fn decode(&mut self, path: String, ty: &IdlType, data: &mut &[u8]) -> Option<()> {
+        let value = match ty {
+            IdlType::Bool => (take::<1>(data)?[0] != 0).to_string(),
+            IdlType::U8 => u8::from_le_bytes(take(data)?).to_string(),
+            IdlType::I8 => i8::from_le_bytes(take(data)?).to_string(),
+            IdlType::U16 => u16::from_le_bytes(take(data)?).to_string(),
+            IdlType::I16 => i16::from_le_bytes(take(data)?).to_string(),
+            IdlType::U32 => u32::from_le_bytes(take(data)?).to_string(),
+            IdlType::I32 => i32::from_le_bytes(take(data)?).to_string(),
+            IdlType::F32 => f32::from_le_bytes(take(data)?).to_string(),
+            IdlType::U64 => u64::from_le_bytes(take(data)?).to_string(),
+            IdlType::I64 => i64::from_le_bytes(take(data)?).to_string(),
+            IdlType::F64 => f64::from_le_bytes(take(data)?).to_string(),
+            IdlType::U128 => u128::from_le_bytes(take(data)?).to_string(),
+            IdlType::I128 => i128::from_le_bytes(take(data)?).to_string(),
+            IdlType::U256 | IdlType::I256 => {
+                let mut bytes = take::<32>(data)?;
+                bytes.reverse();
+                format!("0x{}", hex(&bytes))
+            }
+            IdlType::Bytes => hex(take_slice(data)?),
+            IdlType::String => format!("{:?}", std::str::from_utf8(take_slice(data)?).ok()?),
+            IdlType::PublicKey => Pubkey::new_from_array(take(data)?).to_string(),
+            IdlType::Option(ty) => match take::<1>(data)?[0] {
+                0 => "None".to_string(),
+                _ => return self.decode(path, ty, data),
+            },
+            IdlType::Vec(ty) => {
+                let len = u32::from_le_bytes(take(data)?);
+                if len == 0 {
+                    "[]".to_string()
+                } else {
+                    return self.decode_items(&path, ty, len as usize, data);
+                }
+            }
+            IdlType::Array(ty, len) => return self.decode_items(&path, ty, *len, data),
+            IdlType::Defined(name) => {
+                let definition = self
+                    .idl
+                    .types
+                    .iter()
+                    .chain(&self.idl.accounts)
+                    .find(|definition| &definition.name == name)?;
+                return self.decode_type_definition(path, &definition.ty, data);
+            }
+        };
+        self.fields.push((path, value));
+        Some(())
+    }
+
+    fn decode_items(
+        &mut self,
+        path: &str,
+        ty: &IdlType,
+        len: usize,
+        data: &mut &[u8],
+    ) -> Option<()> {
+        (0..len).try_for_each(|index| self.decode(format!("{path}[{index}]"), ty, data))
+    }
+}
+
+fn field_path(path: &str, name: &str) -> String {
+    match path.is_empty() {
+        true => name.to_string(),
+        false => format!("{path}.{name}"),
+    }
+}
+
+fn take<const N: usize>(data: &mut &[u8]) -> Option<[u8; N]> {
+    let bytes = data.get(..N)?.try_into().ok()?;
+    *data = &data[N..];
+    Some(bytes)
+}
+
+/// Takes the `u32` length prefixed bytes.
+fn take_slice<'a>(data: &mut &'a [u8]) -> Option<&'a [u8]> {
+    let len = u32::from_le_bytes(take(data)?) as usize;
+    let bytes = data.get(..len)?;
+    *data = &data[len..];
+    Some(bytes)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn serialize_hex<S: serde::Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&hex(bytes))
+}
+
+mod pubkey_string {
+    use anchor_client::solana_sdk::pubkey::Pubkey;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+    use std::str::FromStr;
+
+    pub fn serialize<S: Serializer>(pubkey: &Pubkey, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&pubkey.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
+        Pubkey::from_str(&String::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
+
+mod base64_data {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(data: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&base64::encode(data))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        base64::decode(String::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
@@ -0,0 +1,1088 @@
+use crate::SendOptions;
+use anchor_client::{
+    solana_client::{
+        client_error::{ClientError as SolanaClientError, ClientErrorKind},
+        nonblocking::pubsub_client::PubsubClient,
+        nonblocking::rpc_client::RpcClient,
+        rpc_config::{
+            RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcSendTransactionConfig,
+            RpcSimulateTransactionAccountsConfig, RpcSimulateTransactionConfig,
+            RpcTransactionConfig, RpcTransactionLogsConfig, RpcTransactionLogsFilter,
+        },
+        rpc_custom_error::JSON_RPC_SERVER_ERROR_SEND_TRANSACTION_PREFLIGHT_FAILURE,
+        rpc_filter::RpcFilterType,
+        rpc_request::{RpcError, RpcResponseErrorData, MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS},
+        rpc_response::{RpcLogsResponse, RpcSimulateTransactionResult},
+    },
+    solana_sdk::{
+        account::{from_account, Account, AccountSharedData},
+        address_lookup_table_account::AddressLookupTableAccount,
+        clock::{Clock, Slot, UnixTimestamp, DEFAULT_MS_PER_SLOT},
+        commitment_config::CommitmentConfig,
+        hash::Hash,
+        message::{v0::LoadedAddresses, Message, VersionedMessage},
+        pubkey::Pubkey,
+        signature::Signature,
+        signer::Signer,
+        system_instruction,
+        sysvar::Sysvar,
+        transaction::{Transaction, TransactionError, VersionedTransaction},
+    },
+    ClientError as Error, Cluster,
+};
+use fehler::{throw, throws};
+use futures::stream::{self, BoxStream, StreamExt};
+use log::debug;
+use solana_account_decoder::{
+    parse_token::{token_amount_to_ui_amount, UiTokenAmount},
+    UiAccountEncoding,
+};
+use solana_address_lookup_table_program::state::AddressLookupTable;
+use solana_program_test::{BanksClientError, ProgramTestContext, ProgramTestError};
+use solana_transaction_status::{
+    option_serializer::OptionSerializer, EncodedConfirmedTransactionWithStatusMeta,
+    EncodedTransaction, EncodedTransactionWithStatusMeta, TransactionBinaryEncoding,
+    TransactionStatusMeta, UiTransactionEncoding, UiTransactionStatusMeta,
+};
+use spl_token_2022::extension::StateWithExtensions;
+use std::{collections::BTreeSet, str::FromStr, time::Duration};
+use tokio::sync::{
+    broadcast::{self, error::RecvError},
+    mpsc, oneshot, Mutex,
+};
+
+/// How long the RPC backend waits for the transaction confirmation.
+const CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(60);
+const CONFIRMATION_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How often the RPC backend checks whether the cluster reached the awaited slot or time.
+const SLOT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The newest transaction version the transactions are fetched with.
+const MAX_SUPPORTED_TRANSACTION_VERSION: u8 = 0;
+
+/// The number of processed transactions buffered for slow subscribers.
+const NOTIFICATIONS_CAPACITY: usize = 1024;
+
+/// The backend [Client](crate::Client) uses to talk to the Solana runtime.
+pub(crate) enum Backend {
+    /// Sends RPC requests to a running cluster, e.g. to `solana-test-validator`.
+    Rpc(RpcClient),
+    /// Processes transactions in-process with the `BanksClient` of a started `ProgramTest`.
+    ProgramTest {
+        context: Mutex<ProgramTestContext>,
+        /// The accounts referenced by the processed transactions, the accounts set directly
+        /// and the genesis accounts added by [Client::with_program_test_and_accounts](crate::Client::with_program_test_and_accounts).
+        ///
+        /// `BanksClient` cannot iterate accounts, so the program accounts are looked up among them.
+        known_accounts: Mutex<BTreeSet<Pubkey>>,
+        /// Announces the processed transactions to the account and logs subscriptions.
+        notifications: broadcast::Sender<ProcessedTransaction>,
+    },
+}
+
+/// A transaction successfully processed by the `ProgramTest` backend.
+#[derive(Clone)]
+pub(crate) struct ProcessedTransaction {
+    signature: Signature,
+    account_keys: Vec<Pubkey>,
+    writable_accounts: Vec<Pubkey>,
+    logs: Vec<String>,
+}
+
+impl Backend {
+    /// Creates the backend processing transactions with the started `ProgramTest`.
+    ///
+    /// The `genesis_accounts` are the known accounts added to the `ProgramTest` before it started.
+    pub(crate) fn program_test(
+        context: ProgramTestContext,
+        genesis_accounts: impl IntoIterator<Item = Pubkey>,
+    ) -> Self {
+        Self::ProgramTest {
+            context: Mutex::new(context),
+            known_accounts: Mutex::new(genesis_accounts.into_iter().collect()),
+            notifications: broadcast::channel(NOTIFICATIONS_CAPACITY).0,
+        }
+    }
+
+    /// Returns the account of the provided [Pubkey] if it exists.
+    #[throws]
+    pub(crate) async fn get_account(&self, address: &Pubkey) -> Option<Account> {
+        match self {
+            Self::Rpc(rpc_client) => {
+                rpc_client
+                    .get_account_with_commitment(address, rpc_client.commitment())
+                    .await?
+                    .value
+            }
+            Self::ProgramTest { context, .. } => context
+                .lock()
+                .await
+                .banks_client
+                .get_account(*address)
+                .await
+                .map_err(banks_error)?,
+        }
+    }
+
+    /// Returns all accounts owned by the program matching all the filters.
+    ///
+    /// The `ProgramTest` backend only finds the known accounts - the accounts referenced by the transactions
+    /// it processed, the accounts set directly and the genesis accounts added through the client.
+    #[throws]
+    pub(crate) async fn get_program_accounts(
+        &self,
+        program_id: &Pubkey,
+        filters: Vec<RpcFilterType>,
+    ) -> Vec<(Pubkey, Account)> {
+        match self {
+            Self::Rpc(rpc_client) => {
+                rpc_client
+                    .get_program_accounts_with_config(
+                        program_id,
+                        RpcProgramAccountsConfig {
+                            filters: Some(filters),
+                            account_config: RpcAccountInfoConfig {
+                                encoding: Some(UiAccountEncoding::Base64),
+                                commitment: Some(rpc_client.commitment()),
+                                ..RpcAccountInfoConfig::default()
+                            },
+                            with_context: None,
+                        },
+                    )
+                    .await?
+            }
+            Self::ProgramTest { known_accounts, .. } => {
+                let addresses = known_accounts.lock().await.clone();
+                let mut accounts = Vec::new();
+                for address in addresses {
+                    match self.get_account(&address).await? {
+                        Some(account)
+                            if account.owner == *program_id
+                                && filters.iter().all(|filter| {
+                                    filter.allows(&AccountSharedData::from(account.clone()))
+                                }) =>
+                        {
+                            accounts.push((address, account))
+                        }
+                        _ => {}
+                    }
+                }
+                accounts
+            }
+        }
+    }
+
+    /// Returns the lamport balance of the account of the provided [Pubkey].
+    #[throws]
+    pub(crate) async fn get_balance(&self, address: &Pubkey) -> u64 {
+        match self {
+            Self::Rpc(rpc_client) => rpc_client.get_balance(address).await?,
+            Self::ProgramTest { context, .. } => context
+                .lock()
+                .await
+                .banks_client
+                .get_balance(*address)
+                .await
+                .map_err(banks_error)?,
+        }
+    }
+
+    /// Returns the token balance of the token account of the provided [Pubkey].
+    #[throws]
+    pub(crate) async fn get_token_balance(&self, address: &Pubkey) -> UiTokenAmount {
+        match self {
+            Self::Rpc(rpc_client) => rpc_client.get_token_account_balance(address).await?,
+            Self::ProgramTest { .. } => {
+                let account = self
+                    .get_account(address)
+                    .await?
+                    .ok_or(Error::AccountNotFound)?;
+                // the base state is the same for both token programs
+                let account =
+                    StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account.data)?
+                        .base;
+                let mint = self
+                    .get_account(&account.mint)
+                    .await?
+                    .ok_or(Error::AccountNotFound)?;
+                let mint =
+                    StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint.data)?.base;
+                token_amount_to_ui_amount(account.amount, mint.decimals)
+            }
+        }
+    }
+
+    /// Returns the minimum balance required to make an account with `data_len` bytes rent exempt.
+    #[throws]
+    pub(crate) async fn get_minimum_balance_for_rent_exemption(&self, data_len: usize) -> u64 {
+        match self {
+            Self::Rpc(rpc_client) => {
+                rpc_client
+                    .get_minimum_balance_for_rent_exemption(data_len)
+                    .await?
+            }
+            Self::ProgramTest { context, .. } => context
+                .lock()
+                .await
+                .banks_client
+                .get_rent()
+                .await
+                .map_err(banks_error)?
+                .minimum_balance(data_len),
+        }
+    }
+
+    /// Returns the latest blockhash.
+    #[throws]
+    pub(crate) async fn get_latest_blockhash(&self) -> Hash {
+        match self {
+            Self::Rpc(rpc_client) => rpc_client.get_latest_blockhash().await?,
+            Self::ProgramTest { context, .. } => context
+                .lock()
+                .await
+                .banks_client
+                .get_latest_blockhash()
+                .await
+                .map_err(banks_error)?,
+        }
+    }
+
+    /// Returns the current slot.
+    #[throws]
+    pub(crate) async fn get_slot(&self) -> Slot {
+        match self {
+            Self::Rpc(rpc_client) => rpc_client.get_slot().await?,
+            Self::ProgramTest { context, .. } => context
+                .lock()
+                .await
+                .banks_client
+                .get_root_slot()
+                .await
+                .map_err(banks_error)?,
+        }
+    }
+
+    /// Returns the sysvar of the cluster, e.g. `Clock` or `Rent`.
+    #[throws]
+    pub(crate) async fn get_sysvar<T: Sysvar>(&self) -> T {
+        let account = self
+            .get_account(&T::id())
+            .await?
+            .ok_or(Error::AccountNotFound)?;
+        from_account::<T, _>(&account).ok_or_else(|| {
+            Error::SolanaClientError(
+                ClientErrorKind::Custom(format!("sysvar {} deserialization failed", T::id()))
+                    .into(),
+            )
+        })?
+    }
+
+    /// Warps to the `slot`.
+    ///
+    /// The `Rpc` backend cannot skip slots, it waits until the cluster reaches the `slot`.
+    #[throws]
+    pub(crate) async fn warp_to_slot(&self, slot: Slot) {
+        match self {
+            Self::Rpc(rpc_client) => {
+                let current_slot = rpc_client.get_slot().await?;
+                if current_slot > slot {
+                    throw!(time_travel_error(format!(
+                        "cannot warp back to the slot {slot}, the current slot is {current_slot}"
+                    )));
+                }
+                while rpc_client.get_slot().await? < slot {
+                    tokio::time::sleep(SLOT_POLL_INTERVAL).await;
+                }
+            }
+            Self::ProgramTest { context, .. } => {
+                if let Err(ProgramTestError::InvalidWarpSlot) =
+                    context.lock().await.warp_to_slot(slot)
+                {
+                    throw!(time_travel_error(format!(
+                        "cannot warp back to the slot {slot}"
+                    )));
+                }
+            }
+        }
+        debug!("warped to the slot {}", slot);
+    }
+
+    /// Sets `Clock::unix_timestamp`.
+    ///
+    /// The `Rpc` backend cannot change the clock, it waits until the cluster reaches the `unix_timestamp`.
+    #[throws]
+    pub(crate) async fn set_clock(&self, unix_timestamp: UnixTimestamp) {
+        let mut clock = self.get_sysvar::<Clock>().await?;
+        match self {
+            Self::Rpc(_) => {
+                if clock.unix_timestamp > unix_timestamp {
+                    throw!(time_travel_error(format!(
+                        "cannot set the clock back to {unix_timestamp}, the current timestamp is {}",
+                        clock.unix_timestamp
+                    )));
+                }
+                while clock.unix_timestamp < unix_timestamp {
+                    tokio::time::sleep(SLOT_POLL_INTERVAL).await;
+                    clock = self.get_sysvar::<Clock>().await?;
+                }
+            }
+            Self::ProgramTest { context, .. } => {
+                clock.unix_timestamp = unix_timestamp;
+                context.lock().await.set_sysvar(&clock);
+            }
+        }
+        debug!("clock set to {}", unix_timestamp);
+    }
+
+    /// Moves `Clock::unix_timestamp` forward by the `duration`.
+    ///
+    /// The `ProgramTest` backend also warps by the number of slots the `duration` takes.
+    #[throws]
+    pub(crate) async fn advance_time(&self, duration: Duration) {
+        let clock = self.get_sysvar::<Clock>().await?;
+        let unix_timestamp = clock.unix_timestamp + duration.as_secs() as UnixTimestamp;
+        if let Self::ProgramTest { .. } = self {
+            let slots = duration.as_millis() as u64 / DEFAULT_MS_PER_SLOT;
+            if slots > 0 {
+                self.warp_to_slot(clock.slot + slots).await?;
+            }
+        }
+        self.set_clock(unix_timestamp).await?
+    }
+
+    /// Sends the signed transaction, waits for its confirmation and returns it together with its meta.
+    ///
+    /// Both legacy and `v0` transactions are supported. The `ProgramTest` backend processes
+    /// the transactions right away, so it ignores the commitment, preflight and retries options.
+    #[throws]
+    pub(crate) async fn send_transaction(
+        &self,
+        transaction: &VersionedTransaction,
+        options: &SendOptions,
+    ) -> EncodedConfirmedTransactionWithStatusMeta {
+        match self {
+            Self::Rpc(rpc_client) => {
+                send_rpc_transactions(rpc_client, std::slice::from_ref(transaction), 1, options)
+                    .await
+                    .remove(0)?
+            }
+            Self::ProgramTest {
+                context,
+                known_accounts,
+                notifications,
+            } => {
+                let loaded_addresses = self.load_addresses(&transaction.message).await?;
+
+                let mut context = context.lock().await;
+                let banks_client = &mut context.banks_client;
+                let static_account_keys = transaction.message.static_account_keys();
+                let account_keys = static_account_keys
+                    .iter()
+                    .chain(&loaded_addresses.writable)
+                    .chain(&loaded_addresses.readonly)
+                    .copied()
+                    .collect::<Vec<_>>();
+
+                let mut pre_balances = Vec::with_capacity(account_keys.len());
+                for account_key in &account_keys {
+                    pre_balances.push(
+                        banks_client
+                            .get_balance(*account_key)
+                            .await
+                            .map_err(banks_error)?,
+                    );
+                }
+                // the fee depends only on the signatures and instructions, so the message with
+                // the loaded addresses inlined costs the same as the original one
+                let fee_message = Message {
+                    header: *transaction.message.header(),
+                    account_keys: account_keys.clone(),
+                    recent_blockhash: *transaction.message.recent_blockhash(),
+                    instructions: transaction.message.instructions().to_vec(),
+                };
+                let fee = banks_client
+                    .get_fee_for_message(fee_message)
+                    .await
+                    .map_err(banks_error)?
+                    .unwrap_or_default();
+
+                let result = banks_client
+                    .process_transaction_with_metadata(transaction.clone())
+                    .await
+                    .map_err(banks_error)?;
+                let (log_messages, compute_units_consumed, return_data) = match result.metadata {
+                    Some(metadata) => (
+                        metadata.log_messages,
+                        metadata.compute_units_consumed,
+                        metadata.return_data,
+                    ),
+                    None => (Vec::new(), 0, None),
+                };
+
+                if let Err(err) = result.result {
+                    throw!(preflight_failure(RpcSimulateTransactionResult {
+                        err: Some(err),
+                        logs: Some(log_messages),
+                        accounts: None,
+                        units_consumed: Some(compute_units_consumed),
+                        return_data: return_data.map(Into::into),
+                    }));
+                }
+
+                let mut post_balances = Vec::with_capacity(account_keys.len());
+                for account_key in &account_keys {
+                    post_balances.push(
+                        banks_client
+                            .get_balance(*account_key)
+                            .await
+                            .map_err(banks_error)?,
+                    );
+                }
+                let slot = banks_client.get_root_slot().await.map_err(banks_error)?;
+                known_accounts
+                    .lock()
+                    .await
+                    .extend(account_keys.iter().copied());
+                // there are no receivers when nobody is subscribed
+                let _ = notifications.send(ProcessedTransaction {
+                    signature: transaction.signatures[0],
+                    account_keys: account_keys.clone(),
+                    writable_accounts: static_account_keys
+                        .iter()
+                        .enumerate()
+                        .filter(|(index, _)| transaction.message.is_maybe_writable(*index))
+                        .map(|(_, account_key)| *account_key)
+                        .chain(loaded_addresses.writable.iter().copied())
+                        .collect(),
+                    logs: log_messages.clone(),
+                });
+
+                let meta = TransactionStatusMeta {
+                    status: Ok(()),
+                    fee,
+                    pre_balances,
+                    post_balances,
+                    log_messages: Some(log_messages),
+                    return_data,
+                    compute_units_consumed: Some(compute_units_consumed),
+                    loaded_addresses,
+                    ..TransactionStatusMeta::default()
+                };
+                let mut meta = UiTransactionStatusMeta::from(meta);
+                // `BanksClient` does not record inner instructions, do not pretend there were none.
+                meta.inner_instructions = OptionSerializer::Skip;
+
+                EncodedConfirmedTransactionWithStatusMeta {
+                    slot,
+                    transaction: EncodedTransactionWithStatusMeta {
+                        transaction: EncodedTransaction::Binary(
+                            base64::encode(bincode::serialize(transaction).map_err(|error| {
+                                Error::SolanaClientError(
+                                    ClientErrorKind::Custom(error.to_string()).into(),
+                                )
+                            })?),
+                            TransactionBinaryEncoding::Base64,
+                        ),
+                        meta: Some(meta),
+                        version: Some(transaction.version()),
+                    },
+                    block_time: None,
+                }
+            }
+        }
+    }
+
+    /// Sends the signed transactions and waits for their confirmation, see [Backend::send_transaction].
+    ///
+    /// The `Rpc` backend submits all transactions first, at most `max_concurrency` requests at once,
+    /// and then confirms them together with one `getSignatureStatuses` request per poll.
+    /// The `ProgramTest` backend processes them one by one, `BanksClient` executes them synchronously,
+    /// so there is no confirmation to wait for.
+    pub(crate) async fn send_transactions(
+        &self,
+        transactions: &[VersionedTransaction],
+        max_concurrency: usize,
+        options: &SendOptions,
+    ) -> Vec<Result<EncodedConfirmedTransactionWithStatusMeta, Error>> {
+        match self {
+            Self::Rpc(rpc_client) => {
+                send_rpc_transactions(rpc_client, transactions, max_concurrency, options).await
+            }
+            Self::ProgramTest { .. } => {
+                let mut results = Vec::with_capacity(transactions.len());
+                for transaction in transactions {
+                    results.push(self.send_transaction(transaction, options).await);
+                }
+                results
+            }
+        }
+    }
+
+    /// Simulates the signed transaction without committing it and returns the result together with
+    /// the state of the `accounts` after the transaction (unless it failed).
+    ///
+    /// `BanksClient` drops the post-simulation accounts and the bank of `ProgramTestContext`
+    /// is private, so the `ProgramTest` backend fails when `accounts` are requested.
+    #[throws]
+    pub(crate) async fn simulate_transaction(
+        &self,
+        transaction: &VersionedTransaction,
+        accounts: &[Pubkey],
+    ) -> RpcSimulateTransactionResult {
+        match self {
+            Self::Rpc(rpc_client) => {
+                rpc_client
+                    .simulate_transaction_with_config(
+                        transaction,
+                        RpcSimulateTransactionConfig {
+                            commitment: Some(rpc_client.commitment()),
+                            encoding: Some(UiTransactionEncoding::Base64),
+                            accounts: Some(RpcSimulateTransactionAccountsConfig {
+                                encoding: Some(UiAccountEncoding::Base64),
+                                addresses: accounts.iter().map(ToString::to_string).collect(),
+                            }),
+                            ..RpcSimulateTransactionConfig::default()
+                        },
+                    )
+                    .await?
+                    .value
+            }
+            Self::ProgramTest { context, .. } => {
+                if !accounts.is_empty() {
+                    throw!(Error::SolanaClientError(
+                        ClientErrorKind::Custom(
+                            "the ProgramTest backend cannot return the accounts after the simulation, \
+                            simulate the transaction on the localnet or send it and read the accounts"
+                                .to_string()
+                        )
+                        .into()
+                    ));
+                }
+                let simulation = match context
+                    .lock()
+                    .await
+                    .banks_client
+                    .simulate_transaction(transaction.clone())
+                    .await
+                {
+                    Ok(simulation) => simulation,
+                    Err(BanksClientError::TransactionError(err)) => {
+                        return simulation_failure(err);
+                    }
+                    Err(error) => throw!(banks_error(error)),
+                };
+                let details = simulation.simulation_details;
+                RpcSimulateTransactionResult {
+                    err: simulation.result.and_then(Result::err),
+                    logs: details.as_ref().map(|details| details.logs.clone()),
+                    accounts: None,
+                    units_consumed: details.as_ref().map(|details| details.units_consumed),
+                    return_data: details
+                        .and_then(|details| details.return_data)
+                        .map(Into::into),
+                }
+            }
+        }
+    }
+
+    /// Resolves the addresses the `v0` message loads from the address lookup tables.
+    #[throws]
+    async fn load_addresses(&self, message: &VersionedMessage) -> LoadedAddresses {
+        let mut loaded_addresses = LoadedAddresses::default();
+        for lookup in message.address_table_lookups().unwrap_or_default() {
+            let table = self.get_address_lookup_table(&lookup.account_key).await?;
+            let lookup_address = |index: &u8| {
+                table
+                    .addresses
+                    .get(*index as usize)
+                    .copied()
+                    .ok_or_else(|| {
+                        Error::SolanaClientError(
+                            TransactionError::InvalidAddressLookupTableIndex.into(),
+                        )
+                    })
+            };
+            for index in &lookup.writable_indexes {
+                loaded_addresses.writable.push(lookup_address(index)?);
+            }
+            for index in &lookup.readonly_indexes {
+                loaded_addresses.readonly.push(lookup_address(index)?);
+            }
+        }
+        loaded_addresses
+    }
+
+    /// Returns the address lookup table stored in the account of the provided [Pubkey].
+    #[throws]
+    pub(crate) async fn get_address_lookup_table(
+        &self,
+        address: &Pubkey,
+    ) -> AddressLookupTableAccount {
+        let account = self
+            .get_account(address)
+            .await?
+            .ok_or(Error::AccountNotFound)?;
+        let table = AddressLookupTable::deserialize(&account.data).map_err(|error| {
+            Error::SolanaClientError(TransactionError::InstructionError(0, error).into())
+        })?;
+        AddressLookupTableAccount {
+            key: *address,
+            addresses: table.addresses.to_vec(),
+        }
+    }
+
+    /// Waits until the next slot is reached.
+    ///
+    /// The `ProgramTest` backend does not produce slots on its own, so it warps to the next one.
+    #[throws]
+    pub(crate) async fn wait_for_next_slot(&self) {
+        match self {
+            Self::Rpc(rpc_client) => {
+                let slot = rpc_client.get_slot().await?;
+                while rpc_client.get_slot().await? <= slot {
+                    tokio::time::sleep(SLOT_POLL_INTERVAL).await;
+                }
+            }
+            Self::ProgramTest { context, .. } => {
+                warp_to_next_slot(&mut *context.lock().await).await?
+            }
+        }
+    }
+
+    /// Subscribes to the changes of the account.
+    ///
+    /// The subscription is confirmed when the function returns, so no later change is missed.
+    #[throws]
+    pub(crate) async fn subscribe_account(&self, address: &Pubkey) -> BoxStream<'_, Account> {
+        let address = *address;
+        match self {
+            Self::Rpc(rpc_client) => {
+                let websocket_url = websocket_url(rpc_client)?;
+                let config = RpcAccountInfoConfig {
+                    encoding: Some(UiAccountEncoding::Base64),
+                    commitment: Some(rpc_client.commitment()),
+                    ..RpcAccountInfoConfig::default()
+                };
+                let (sender, receiver) = mpsc::unbounded_channel();
+                let (subscribed_sender, subscribed) = oneshot::channel();
+                tokio::spawn(async move {
+                    let pubsub_client = match PubsubClient::new(&websocket_url).await {
+                        Ok(pubsub_client) => pubsub_client,
+                        Err(error) => return subscribed_sender.send(Err(error)).unwrap_or(()),
+                    };
+                    let (mut updates, unsubscribe) = match pubsub_client
+                        .account_subscribe(&address, Some(config))
+                        .await
+                    {
+                        Ok(subscription) => subscription,
+                        Err(error) => return subscribed_sender.send(Err(error)).unwrap_or(()),
+                    };
+                    let _ = subscribed_sender.send(Ok(()));
+                    loop {
+                        tokio::select! {
+                            update = updates.next() => match update {
+                                Some(update) => {
+                                    if let Some(account) = update.value.decode::<Account>() {
+                                        let _ = sender.send(account);
+                                    }
+                                }
+                                None => break,
+                            },
+                            _ = sender.closed() => break,
+                        }
+                    }
+                    unsubscribe().await;
+                });
+                subscribed.await.map_err(|_| subscription_closed())??;
+                receiver_stream(receiver)
+            }
+            Self::ProgramTest { notifications, .. } => {
+                let receiver = notifications.subscribe();
+                stream::unfold(receiver, move |mut receiver| async move {
+                    loop {
+                        match receiver.recv().await {
+                            Ok(transaction) if transaction.writable_accounts.contains(&address) => {
+                                // the closed accounts are announced as empty system accounts like by the RPC
+                                if let Ok(account) = self.get_account(&address).await {
+                                    return Some((account.unwrap_or_default(), receiver));
+                                }
+                            }
+                            Ok(_) | Err(RecvError::Lagged(_)) => {}
+                            Err(RecvError::Closed) => return None,
+                        }
+                    }
+                })
+                .boxed()
+            }
+        }
+    }
+
+    /// Subscribes to the logs of the transactions mentioning the program.
+    ///
+    /// The subscription is confirmed when the function returns, so no later transaction is missed.
+    #[throws]
+    pub(crate) async fn subscribe_logs(
+        &self,
+        program_id: &Pubkey,
+    ) -> BoxStream<'_, RpcLogsResponse> {
+        let program_id = *program_id;
+        match self {
+            Self::Rpc(rpc_client) => {
+                let websocket_url = websocket_url(rpc_client)?;
+                let config = RpcTransactionLogsConfig {
+                    commitment: Some(rpc_client.commitment()),
+                };
+                let (sender, receiver) = mpsc::unbounded_channel();
+                let (subscribed_sender, subscribed) = oneshot::channel();
+                tokio::spawn(async move {
+                    let pubsub_client = match PubsubClient::new(&websocket_url).await {
+                        Ok(pubsub_client) => pubsub_client,
+                        Err(error) => return subscribed_sender.send(Err(error)).unwrap_or(()),
+                    };
+                    let filter = RpcTransactionLogsFilter::Mentions(vec![program_id.to_string()]);
+                    let (mut updates, unsubscribe) =
+                        match pubsub_client.logs_subscribe(filter, config).await {
+                            Ok(subscription) => subscription,
+                            Err(error) => return subscribed_sender.send(Err(error)).unwrap_or(()),
+                        };
+                    let _ = subscribed_sender.send(Ok(()));
+                    loop {
+                        tokio::select! {
+                            update = updates.next() => match update {
+                                Some(update) => {
+                                    let _ = sender.send(update.value);
+                                }
+                                None => break,
+                            },
+                            _ = sender.closed() => break,
+                        }
+                    }
+                    unsubscribe().await;
+                });
+                subscribed.await.map_err(|_| subscription_closed())??;
+                receiver_stream(receiver)
+            }
+            Self::ProgramTest { notifications, .. } => {
+                let receiver = notifications.subscribe();
+                stream::unfold(receiver, move |mut receiver| async move {
+                    loop {
+                        match receiver.recv().await {
+                            Ok(transaction) if transaction.account_keys.contains(&program_id) => {
+                                let logs = RpcLogsResponse {
+                                    signature: transaction.signature.to_string(),
+                                    err: None,
+                                    logs: transaction.logs,
+                                };
+                                return Some((logs, receiver));
+                            }
+                            Ok(_) | Err(RecvError::Lagged(_)) => {}
+                            Err(RecvError::Closed) => return None,
+                        }
+                    }
+                })
+                .boxed()
+            }
+        }
+    }
+
+    /// Airdrops lamports to the chosen account.
+    ///
+    /// The `ProgramTest` backend transfers the lamports from its genesis mint account.
+    #[throws]
+    pub(crate) async fn airdrop(&self, address: &Pubkey, lamports: u64) {
+        match self {
+            Self::Rpc(rpc_client) => {
+                let signature = rpc_client.request_airdrop(address, lamports).await?;
+                loop {
+                    match rpc_client.get_signature_status(&signature).await? {
+                        Some(Ok(_)) => break,
+                        Some(Err(transaction_error)) => {
+                            throw!(Error::SolanaClientError(transaction_error.into()))
+                        }
+                        None => tokio::time::sleep(Duration::from_millis(500)).await,
+                    }
+                }
+            }
+            Self::ProgramTest { context, .. } => {
+                let mut context = context.lock().await;
+                let context = &mut *context;
+                let blockhash = context
+                    .banks_client
+                    .get_latest_blockhash()
+                    .await
+                    .map_err(banks_error)?;
+                let transaction = Transaction::new_signed_with_payer(
+                    &[system_instruction::transfer(
+                        &context.payer.pubkey(),
+                        address,
+                        lamports,
+                    )],
+                    Some(&context.payer.pubkey()),
+                    &[&context.payer],
+                    blockhash,
+                );
+                context
+                    .banks_client
+                    .process_transaction(transaction)
+                    .await
+                    .map_err(banks_error)?;
+            }
+        }
+        debug!("{} lamports airdropped", lamports);
+    }
+
+    /// Overwrites the account, or creates it when it does not exist.
+    ///
+    /// The accounts of a running cluster cannot be overwritten,
+    /// the `Rpc` backend requires the accounts to be set in the genesis of the localnet instead.
+    #[throws]
+    pub(crate) async fn set_account(&self, address: &Pubkey, account: Account) {
+        match self {
+            Self::Rpc(_) => throw!(Error::SolanaClientError(
+                ClientErrorKind::Custom(
+                    "accounts of a running cluster cannot be set, \
+                    start the localnet with `Commander::start_localnet_with_accounts` instead"
+                        .to_string()
+                )
+                .into()
+            )),
+            Self::ProgramTest {
+                context,
+                known_accounts,
+                ..
+            } => {
+                context
+                    .lock()
+                    .await
+                    .set_account(address, &AccountSharedData::from(account));
+                known_accounts.lock().await.insert(*address);
+            }
+        }
+        debug!("account {} set", address);
+    }
+}
+
+/// Submits the transactions to the RPC node and waits for their confirmation, see [Backend::send_transactions].
+async fn send_rpc_transactions(
+    rpc_client: &RpcClient,
+    transactions: &[VersionedTransaction],
+    max_concurrency: usize,
+    options: &SendOptions,
+) -> Vec<Result<EncodedConfirmedTransactionWithStatusMeta, Error>> {
+    let max_concurrency = max_concurrency.max(1);
+    let signatures = stream::iter(transactions)
+        .map(|transaction| {
+            rpc_client.send_transaction_with_config(
+                transaction,
+                RpcSendTransactionConfig {
+                    skip_preflight: options.skip_preflight,
+                    preflight_commitment: Some(options.commitment.commitment),
+                    max_retries: options.max_retries,
+                    ..RpcSendTransactionConfig::default()
+                },
+            )
+        })
+        .buffered(max_concurrency)
+        .collect::<Vec<_>>()
+        .await;
+
+    let mut statuses = signatures
+        .into_iter()
+        .map(|signature| Ok((signature?, None)))
+        .collect::<Vec<Result<(Signature, Option<Result<(), TransactionError>>), Error>>>();
+    let mut waited = Duration::ZERO;
+    for poll in 0.. {
+        let pending = statuses
+            .iter()
+            .enumerate()
+            .filter_map(|(index, status)| match status {
+                Ok((signature, None)) => Some((index, *signature)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        if pending.is_empty() {
+            break;
+        }
+        if poll > 0 {
+            if waited >= CONFIRMATION_TIMEOUT {
+                for (index, signature) in pending {
+                    statuses[index] = Err(Error::SolanaClientError(
+                        ClientErrorKind::Custom(format!(
+                            "transaction {signature} was not confirmed in {}s",
+                            CONFIRMATION_TIMEOUT.as_secs()
+                        ))
+                        .into(),
+                    ));
+                }
+                break;
+            }
+            tokio::time::sleep(CONFIRMATION_POLL_INTERVAL).await;
+            waited += CONFIRMATION_POLL_INTERVAL;
+        }
+
+        for chunk in pending.chunks(MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS) {
+            let signatures = chunk
+                .iter()
+                .map(|(_, signature)| *signature)
+                .collect::<Vec<_>>();
+            match rpc_client.get_signature_statuses(&signatures).await {
+                Ok(response) => {
+                    for ((index, signature), status) in chunk.iter().zip(response.value) {
+                        if let Some(status) =
+                            status.filter(|status| status.satisfies_commitment(options.commitment))
+                        {
+                            statuses[*index] = Ok((*signature, Some(status.status)));
+                        }
+                    }
+                }
+                // the error is not `Clone`, every transaction of the chunk gets its message
+                Err(error) => {
+                    for (index, _) in chunk {
+                        statuses[*index] = Err(Error::SolanaClientError(
+                            ClientErrorKind::Custom(error.to_string()).into(),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    // `getTransaction` does not support the `processed` commitment
+    let commitment = if options.commitment.is_at_least_confirmed() {
+        options.commitment
+    } else {
+        CommitmentConfig::confirmed()
+    };
+    stream::iter(statuses)
+        .map(|status| async move {
+            let (signature, status) = status?;
+            let transaction = rpc_client
+                .get_transaction_with_config(
+                    &signature,
+                    RpcTransactionConfig {
+                        encoding: Some(UiTransactionEncoding::Binary),
+                        commitment: Some(commitment),
+                        max_supported_transaction_version: Some(MAX_SUPPORTED_TRANSACTION_VERSION),
+                    },
+                )
+                .await?;
+
+            // the transactions sent without the preflight fail on-chain,
+            // report them the same way as the rejected ones
+            if let Some(Err(err)) = status {
+                let meta = transaction.transaction.meta;
+                return Err(preflight_failure(RpcSimulateTransactionResult {
+                    err: Some(err),
+                    logs: meta
+                        .as_ref()
+                        .and_then(|meta| meta.log_messages.clone().into()),
+                    accounts: None,
+                    units_consumed: meta
+                        .as_ref()
+                        .and_then(|meta| meta.compute_units_consumed.clone().into()),
+                    return_data: meta.and_then(|meta| meta.return_data.into()),
+                }));
+            }
+            Ok(transaction)
+        })
+        .buffered(max_concurrency)
+        .collect()
+        .await
+}
+
+/// Warps the `ProgramTest` to the slot after the current one.
+#[throws]
+async fn warp_to_next_slot(context: &mut ProgramTestContext) {
+    let mut slot = context
+        .banks_client
+        .get_root_slot()
+        .await
+        .map_err(banks_error)?
+        + 1;
+    while let Err(ProgramTestError::InvalidWarpSlot) = context.warp_to_slot(slot) {
+        slot += 1;
+    }
+}
+
+/// Returns the error of the time travel to the past.
+fn time_travel_error(message: String) -> Error {
+    Error::SolanaClientError(ClientErrorKind::Custom(message).into())
+}
+
+/// Returns the websocket URL of the cluster the RPC client is connected to.
+#[throws]
+fn websocket_url(rpc_client: &RpcClient) -> String {
+    Cluster::from_str(&rpc_client.url())
+        .map_err(|error| {
+            Error::SolanaClientError(ClientErrorKind::Custom(error.to_string()).into())
+        })?
+        .ws_url()
+        .to_string()
+}
+
+/// Turns the channel receiver of the subscription updates into a stream.
+fn receiver_stream<'a, T: Send + 'a>(receiver: mpsc::UnboundedReceiver<T>) -> BoxStream<'a, T> {
+    stream::unfold(receiver, |mut receiver| async move {
+        receiver.recv().await.map(|update| (update, receiver))
+    })
+    .boxed()
+}
+
+fn subscription_closed() -> Error {
+    Error::SolanaClientError(
+        ClientErrorKind::Custom("the subscription task failed".to_string()).into(),
+    )
+}
+
+/// The result of the simulation of the transaction rejected before its processing.
+fn simulation_failure(err: TransactionError) -> RpcSimulateTransactionResult {
+    RpcSimulateTransactionResult {
+        err: Some(err),
+        logs: None,
+        accounts: None,
+        units_consumed: None,
+        return_data: None,
+    }
+}
+
+/// Creates the same error the RPC returns when the transaction simulation fails,
+/// so both backends can be handled (and reported) the same way.
+pub(crate) fn preflight_failure(result: RpcSimulateTransactionResult) -> Error {
+    let message = format!(
+        "Transaction simulation failed: {}",
+        result
+            .err
+            .as_ref()
+            .map(ToString::to_string)
+            .unwrap_or_default()
+    );
+    Error::SolanaClientError(SolanaClientError::from(ClientErrorKind::RpcError(
+        RpcError::RpcResponseError {
+            code: JSON_RPC_SERVER_ERROR_SEND_TRANSACTION_PREFLIGHT_FAILURE,
+            message,
+            data: RpcResponseErrorData::SendTransactionPreflightFailure(result),
+        },
+    )))
+}
+
+/// Converts [BanksClientError] into the error used by [Client](crate::Client).
+fn banks_error(error: BanksClientError) -> Error {
+    match error {
+        BanksClientError::TransactionError(err) => Error::SolanaClientError(err.into()),
+        BanksClientError::SimulationError {
+            err,
+            logs,
+            units_consumed,
+            return_data,
+        } => preflight_failure(RpcSimulateTransactionResult {
+            err: Some(err),
+            logs: Some(logs),
+            accounts: None,
+            units_consumed: Some(units_consumed),
+            return_data: return_data.map(Into::into),
+        }),
+        BanksClientError::Io(err) => Error::IOError(err),
+        error => Error::SolanaClientError(ClientErrorKind::Custom(error.to_string()).into()),
+    }
+}
@@ -1,54 +1,106 @@
-use crate::{config::CONFIG, Reader, TempClone};
+use crate::{
+    backend::Backend, compute_units::record_compute_units, config::CONFIG,
+    reader::Error as ReaderError, AccountDecodeFailure, AccountSnapshot, AccountTypes,
+    MintExtension, OfflineTransaction, Reader, SendOptions, SimulationOutcome, TempClone,
+    TransactionOutcome, UpgradeReport,
+};
 use anchor_client::{
     anchor_lang::{
-        prelude::System, solana_program::program_pack::Pack, AccountDeserialize, Id,
-        InstructionData, ToAccountMetas,
+        error::ErrorCode, prelude::System, solana_program::program_pack::Pack, AccountDeserialize,
+        AccountSerialize, Discriminator, Event, Id, InstructionData, ToAccountMetas, ZeroCopy,
+    },
+    solana_client::{
+        client_error::ClientErrorKind,
+        nonblocking::rpc_client::RpcClient,
+        rpc_filter::{Memcmp, RpcFilterType},
+        rpc_response::RpcLogsResponse,
     },
-    solana_client::rpc_config::RpcTransactionConfig,
     solana_sdk::{
         account::Account,
+        address_lookup_table_account::AddressLookupTableAccount,
         bpf_loader,
+        bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+        clock::{Clock, Slot, UnixTimestamp},
         commitment_config::CommitmentConfig,
-        instruction::Instruction,
+        epoch_schedule::EpochSchedule,
+        fee_calculator::DEFAULT_TARGET_LAMPORTS_PER_SIGNATURE,
+        instruction::{Instruction, InstructionError},
         loader_instruction,
+        message::{v0, Message, VersionedMessage},
+        native_token::LAMPORTS_PER_SOL,
+        nonce::{
+            state::{Data as NonceData, Versions as NonceVersions},
+            State as NonceState,
+        },
+        program_error::ProgramError,
         pubkey::Pubkey,
+        rent::Rent,
         signer::{keypair::Keypair, Signer},
-        system_instruction,
-        transaction::Transaction,
+        stake::{
+            self, instruction as stake_instruction,
+            state::{Authorized, Lockup, StakeState},
+        },
+        system_instruction, system_program,
+        transaction::{Transaction, VersionedTransaction},
+        vote::{
+            self,
+            instruction::{self as vote_instruction, CreateVoteAccountConfig},
+            state::{VoteInit, VoteState},
+        },
     },
     Client as AnchorClient, ClientError as Error, Cluster, Program,
 };
 
 use borsh::BorshDeserialize;
+use bytemuck::Pod;
 use fehler::{throw, throws};
-use futures::stream::{self, StreamExt};
+use futures::{
+    stream::{self, BoxStream, StreamExt},
+    Future,
+};
 use log::debug;
 use serde::de::DeserializeOwned;
 use solana_account_decoder::parse_token::UiTokenAmount;
+use solana_address_lookup_table_program::instruction as address_lookup_table_instruction;
 use solana_cli_output::display::println_transaction;
-use solana_transaction_status::{EncodedConfirmedTransactionWithStatusMeta, UiTransactionEncoding};
+use solana_program_test::ProgramTest;
+use solana_transaction_status::EncodedConfirmedTransactionWithStatusMeta;
 // The deprecated `create_associated_token_account` function is used because of different versions
 // of some crates are required in this `client` crate and `anchor-spl` crate
 #[allow(deprecated)]
-use spl_associated_token_account::{create_associated_token_account, get_associated_token_address};
-use std::{mem, rc::Rc};
+use spl_associated_token_account::{
+    get_associated_token_address_with_program_id,
+    instruction::{create_associated_token_account, create_associated_token_account_idempotent},
+};
+use spl_token_2022::{
+    extension::{BaseStateWithExtensions, Extension, ExtensionType, StateWithExtensions},
+    instruction::{self as token_instruction, AuthorityType},
+};
+use std::{io, mem, rc::Rc};
 use std::{thread::sleep, time::Duration};
 
 // @TODO: Make compatible with the latest Anchor deps.
 // https://github.com/project-serum/anchor/pull/1307#issuecomment-1022592683
 
 const RETRY_LOCALNET_EVERY_MILLIS: u64 = 500;
+const PROGRAM_TEST_PAYER_LAMPORTS: u64 = 1_000 * LAMPORTS_PER_SOL;
+const PROGRAM_DATA_CHUNK_SIZE: usize = 900;
 
 type Payer = Rc<Keypair>;
 
 /// `Client` allows you to send typed RPC requests to a Solana cluster.
+///
+/// The requests are sent to the localnet by default. Use [Client::with_program_test]
+/// to process them in-process by the `BanksClient` of [ProgramTest] instead.
 pub struct Client {
     payer: Keypair,
     anchor_client: AnchorClient<Payer>,
+    backend: Backend,
+    send_options: SendOptions,
 }
 
 impl Client {
-    /// Creates a new `Client` instance.
+    /// Creates a new `Client` instance connected to the localnet.
     pub fn new(payer: Keypair) -> Self {
         Self {
             payer: payer.clone(),
@@ -57,7 +109,124 @@ impl Client {
                 Rc::new(payer),
                 CommitmentConfig::confirmed(),
             ),
+            backend: Backend::Rpc(RpcClient::new_with_commitment(
+                Cluster::Localnet.url().to_string(),
+                CommitmentConfig::confirmed(),
+            )),
+            send_options: SendOptions::default(),
+        }
+    }
+
+    /// Creates a new `Client` instance sending the requests with the `rpc_client`,
+    /// e.g. connected to another cluster or with a custom `RpcSender`.
+    ///
+    /// _Note_: [Client::anchor_client], [Client::program] and [Client::is_localnet_running]
+    /// always communicate with the localnet. [Client::with_send_options] recreates the `rpc_client`
+    /// from its URL, set the commitment of the `rpc_client` instead.
+    pub fn with_rpc_client(payer: Keypair, rpc_client: RpcClient) -> Self {
+        Self {
+            payer: payer.clone(),
+            anchor_client: AnchorClient::new_with_options(
+                Cluster::Localnet,
+                Rc::new(payer),
+                rpc_client.commitment(),
+            ),
+            backend: Backend::Rpc(rpc_client),
+            send_options: SendOptions::default(),
+        }
+    }
+
+    /// Creates a new `Client` instance processing transactions in-process
+    /// with the started [ProgramTest] - no validator is required.
+    ///
+    /// The `payer` account is funded in the genesis so you don't need to airdrop to it.
+    ///
+    /// _Note_: [Client::anchor_client], [Client::program] and [Client::is_localnet_running]
+    /// always communicate with the localnet.
+    ///
+    /// _Note_: The accounts added by [ProgramTest::add_account] cannot be listed by [Client::program_accounts],
+    /// add them with [Client::with_program_test_and_accounts] instead.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let program_test = ProgramTest::new("turnstile", turnstile::ID, processor!(turnstile::entry));
+    /// let client = Client::with_program_test(system_keypair(0), program_test).await;
+    /// ```
+    pub async fn with_program_test(payer: Keypair, program_test: ProgramTest) -> Self {
+        Self::with_program_test_and_accounts(payer, program_test, []).await
+    }
+
+    /// Creates a new `Client` instance like [Client::with_program_test]
+    /// with the `accounts` added to the genesis of the [ProgramTest].
+    ///
+    /// Unlike the accounts added by [ProgramTest::add_account], these accounts are found by [Client::program_accounts].
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let state = Client::anchor_account(turnstile::ID, &turnstile::State::default())?;
+    /// let client = Client::with_program_test_and_accounts(
+    ///     system_keypair(0),
+    ///     program_test,
+    ///     [(keypair(42).pubkey(), state)],
+    /// )
+    /// .await;
+    /// ```
+    pub async fn with_program_test_and_accounts(
+        payer: Keypair,
+        mut program_test: ProgramTest,
+        accounts: impl IntoIterator<Item = (Pubkey, Account)>,
+    ) -> Self {
+        program_test.add_account(
+            payer.pubkey(),
+            Account {
+                lamports: PROGRAM_TEST_PAYER_LAMPORTS,
+                owner: system_program::ID,
+                ..Account::default()
+            },
+        );
+        let mut genesis_accounts = vec![payer.pubkey()];
+        for (address, account) in accounts {
+            program_test.add_account(address, account);
+            genesis_accounts.push(address);
+        }
+        let context = program_test.start_with_context().await;
+        Self {
+            payer: payer.clone(),
+            anchor_client: AnchorClient::new_with_options(
+                Cluster::Localnet,
+                Rc::new(payer),
+                CommitmentConfig::confirmed(),
+            ),
+            backend: Backend::program_test(context, genesis_accounts),
+            send_options: SendOptions::default(),
+        }
+    }
+
+    /// Sets the [SendOptions] applied to all transactions sent by this client,
+    /// unless the options are passed explicitly, e.g. to [Client::send_transaction_with_options].
+    ///
+    /// The requests of the client (and of [Client::anchor_client]) use the commitment of the options.
+    pub fn with_send_options(mut self, send_options: SendOptions) -> Self {
+        self.anchor_client = AnchorClient::new_with_options(
+            Cluster::Localnet,
+            Rc::new(self.payer.clone()),
+            send_options.commitment,
+        );
+        if let Backend::Rpc(rpc_client) = &self.backend {
+            self.backend = Backend::Rpc(RpcClient::new_with_commitment(
+                rpc_client.url(),
+                send_options.commitment,
+            ));
         }
+        self.send_options = send_options;
+        self
+    }
+
+    /// Gets the [SendOptions] applied to all transactions sent by this client.
+    pub fn send_options(&self) -> &SendOptions {
+        &self.send_options
     }
 
     /// Gets client's payer.
@@ -71,8 +240,9 @@ impl Client {
     }
 
     /// Creates [Program] instance to communicate with the selected program.
+    #[throws]
     pub fn program(&self, program_id: Pubkey) -> Program<Payer> {
-        self.anchor_client.program(program_id).unwrap()
+        self.anchor_client.program(program_id)?
     }
 
     /// Finds out if the Solana localnet is running.
@@ -80,11 +250,10 @@ impl Client {
     /// Set `retry` to `true` when you want to wait for up to 15 seconds until
     /// the localnet is running (until 30 retries with 500ms delays are performed).
     pub async fn is_localnet_running(&self, retry: bool) -> bool {
-        let rpc_client = self
-            .anchor_client
-            .program(System::id())
-            .unwrap()
-            .async_rpc();
+        let rpc_client = match self.anchor_client.program(System::id()) {
+            Ok(program) => program.async_rpc(),
+            Err(_) => return false,
+        };
 
         for _ in 0..(if retry {
             CONFIG.test.validator_startup_timeout / RETRY_LOCALNET_EVERY_MILLIS
@@ -114,8 +283,12 @@ impl Client {
     where
         T: AccountDeserialize + Send + 'static,
     {
-        let program = self.anchor_client.program(System::id()).unwrap();
-        program.account::<T>(account).await.unwrap()
+        let account = self
+            .get_account(account)
+            .await?
+            .ok_or(Error::AccountNotFound)?;
+
+        T::try_deserialize(&mut account.data.as_slice())?
     }
 
     /// Gets deserialized data from the chosen account serialized with Bincode
@@ -159,7 +332,106 @@ impl Client {
             .ok_or(Error::AccountNotFound)?;
 
         T::try_from_slice(&account.data)
-            .map_err(|_| Error::LogParseError("Bincode deserialization failed".to_string()))?
+            .map_err(|_| Error::LogParseError("Borsh deserialization failed".to_string()))?
+    }
+
+    /// Gets the data of the chosen `#[account(zero_copy)]` account, i.e. the account used with `AccountLoader`.
+    ///
+    /// The account discriminator is checked and the rest of the data is cast to the type with `bytemuck`.
+    ///
+    /// # Errors
+    ///
+    /// It fails when:
+    /// - the account does not exist.
+    /// - the Solana cluster is not running.
+    /// - the account discriminator does not match.
+    /// - the account data is smaller than the type.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let order_book = client.account_data_zero_copy::<dex::OrderBook>(order_book_address).await?;
+    /// assert_eq!(order_book.bids_len, 1);
+    /// ```
+    #[throws]
+    pub async fn account_data_zero_copy<T>(&self, account: Pubkey) -> T
+    where
+        T: ZeroCopy + Send + 'static,
+    {
+        let account = self
+            .get_account(account)
+            .await?
+            .ok_or(Error::AccountNotFound)?;
+
+        let discriminator = account
+            .data
+            .get(..8)
+            .ok_or_else(|| Error::AnchorError(ErrorCode::AccountDiscriminatorNotFound.into()))?;
+        if discriminator != T::DISCRIMINATOR {
+            throw!(Error::AnchorError(
+                ErrorCode::AccountDiscriminatorMismatch.into()
+            ));
+        }
+        let data = account
+            .data
+            .get(8..8 + mem::size_of::<T>())
+            .ok_or_else(|| Error::AnchorError(ErrorCode::AccountDidNotDeserialize.into()))?;
+
+        // the account data are not guaranteed to be aligned for `T`
+        bytemuck::pod_read_unaligned(data)
+    }
+
+    /// Gets all accounts of the chosen Anchor account type owned by the program.
+    ///
+    /// The accounts are matched by the account discriminator and by all the additional `filters`,
+    /// e.g. [Memcmp](anchor_client::solana_client::rpc_filter::Memcmp) to match a field
+    /// or `RpcFilterType::DataSize` to match the account size. Memcmp offsets include the 8-byte discriminator.
+    ///
+    /// _Note_: The `ProgramTest` backend cannot iterate the accounts, so it only finds the accounts
+    /// referenced by the transactions sent through this client, the accounts set by [Client::set_account]
+    /// and the accounts passed to [Client::with_program_test_and_accounts].
+    /// The accounts added by [ProgramTest::add_account] directly are missing.
+    ///
+    /// # Errors
+    ///
+    /// It fails when:
+    /// - the Solana cluster is not running.
+    /// - deserialization of a matching account failed.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// // all escrows initialized by Alice
+    /// let escrows = client
+    ///     .program_accounts::<escrow::EscrowAccount>(
+    ///         escrow::ID,
+    ///         vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(8, alice.pubkey().to_bytes().to_vec()))],
+    ///     )
+    ///     .await?;
+    /// ```
+    #[throws]
+    pub async fn program_accounts<T>(
+        &self,
+        program_id: Pubkey,
+        filters: Vec<RpcFilterType>,
+    ) -> Vec<(Pubkey, T)>
+    where
+        T: AccountDeserialize + Discriminator + Send + 'static,
+    {
+        let mut filters = filters;
+        filters.insert(
+            0,
+            RpcFilterType::Memcmp(Memcmp::new_raw_bytes(0, T::DISCRIMINATOR.to_vec())),
+        );
+
+        self.backend
+            .get_program_accounts(&program_id, filters)
+            .await?
+            .into_iter()
+            .map(|(address, account)| {
+                Ok((address, T::try_deserialize(&mut account.data.as_slice())?))
+            })
+            .collect::<Result<_, Error>>()?
     }
 
     /// Returns all information associated with the account of the provided [Pubkey].
@@ -169,16 +441,186 @@ impl Client {
     /// It fails when the Solana cluster is not running.
     #[throws]
     pub async fn get_account(&self, account: Pubkey) -> Option<Account> {
-        let rpc_client = self.anchor_client.program(System::id())?.async_rpc();
-        rpc_client
-            .get_account_with_commitment(&account, rpc_client.commitment())
-            .await
-            .unwrap()
-            .value
+        self.backend.get_account(&account).await?
+    }
+
+    /// Takes the snapshot of the lamports, owner and data of the accounts,
+    /// see [AccountSnapshot::diff].
+    ///
+    /// # Errors
+    ///
+    /// It fails when the Solana cluster is not running.
+    #[throws]
+    pub async fn snapshot(&self, addresses: &[Pubkey]) -> AccountSnapshot {
+        let slot = self.backend.get_slot().await?;
+        let mut accounts = Vec::with_capacity(addresses.len());
+        for address in addresses {
+            accounts.push((*address, self.backend.get_account(address).await?));
+        }
+        AccountSnapshot::new(slot, accounts)
+    }
+
+    /// Overwrites the account, or creates it when it does not exist,
+    /// e.g. to set up a fixture or to mock an account of a foreign program.
+    ///
+    /// _Note_: The accounts of a running localnet cannot be set,
+    /// pass them to [Commander::start_localnet_with_accounts](crate::Commander::start_localnet_with_accounts)
+    /// to create them in the genesis instead.
+    ///
+    /// # Errors
+    ///
+    /// It fails with the `Rpc` backend, only the `ProgramTest` backend supports it.
+    #[throws]
+    pub async fn set_account(&self, address: Pubkey, account: Account) {
+        self.backend.set_account(&address, account).await?
+    }
+
+    /// Overwrites the account with the Anchor account `value` owned by the `owner` program,
+    /// see [Client::set_account].
+    ///
+    /// The account is rent exempt and its data start with the discriminator of `T`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// client
+    ///     .set_anchor_account(
+    ///         escrow.pubkey(),
+    ///         escrow::ID,
+    ///         &escrow::Escrow {
+    ///             initializer_key: alice.pubkey(),
+    ///             initializer_amount: 500,
+    ///             ..Default::default()
+    ///         },
+    ///     )
+    ///     .await?;
+    /// ```
+    #[throws]
+    pub async fn set_anchor_account<T: AccountSerialize>(
+        &self,
+        address: Pubkey,
+        owner: Pubkey,
+        value: &T,
+    ) {
+        self.set_account(address, Self::anchor_account(owner, value)?)
+            .await?
+    }
+
+    /// Creates the rent exempt account with the Anchor account `value` owned by the `owner` program,
+    /// e.g. for [Commander::start_localnet_with_accounts](crate::Commander::start_localnet_with_accounts).
+    ///
+    /// The data start with the discriminator of `T`.
+    #[throws]
+    pub fn anchor_account<T: AccountSerialize>(owner: Pubkey, value: &T) -> Account {
+        let mut data = Vec::new();
+        value.try_serialize(&mut data)?;
+        Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    /// Subscribes to the changes of the account over the pubsub websocket of the cluster.
+    ///
+    /// The subscription is active when the function returns, so no later change is missed.
+    /// The subscription ends when the stream is dropped.
+    ///
+    /// _Note_: The `ProgramTest` backend announces the changes made by the transactions sent through this client.
+    ///
+    /// # Errors
+    ///
+    /// It fails when the subscription cannot be created, e.g. when the Solana cluster is not running.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let mut updates = client.subscribe_account(state).await?;
+    /// turnstile_instruction::coin(&client, /* ... */).await?;
+    /// let account = tokio::time::timeout(Duration::from_secs(5), updates.next()).await?;
+    /// ```
+    #[throws]
+    pub async fn subscribe_account(&self, account: Pubkey) -> BoxStream<'_, Account> {
+        self.backend.subscribe_account(&account).await?
+    }
+
+    /// Subscribes to the changes of the account and deserializes them as the chosen Anchor account type.
+    ///
+    /// See [Client::subscribe_account] for more information.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let mut updates = client.subscribe_account_data::<turnstile::State>(state).await?;
+    /// turnstile_instruction::coin(&client, /* ... */).await?;
+    /// let state = tokio::time::timeout(Duration::from_secs(5), updates.next())
+    ///     .await?
+    ///     .unwrap()?;
+    /// assert!(!state.locked);
+    /// ```
+    #[throws]
+    pub async fn subscribe_account_data<T>(
+        &self,
+        account: Pubkey,
+    ) -> BoxStream<'_, Result<T, Error>>
+    where
+        T: AccountDeserialize + Send + 'static,
+    {
+        self.subscribe_account(account)
+            .await?
+            .map(|account| Ok(T::try_deserialize(&mut account.data.as_slice())?))
+            .boxed()
+    }
+
+    /// Subscribes to the logs of the transactions mentioning the program
+    /// over the pubsub websocket of the cluster.
+    ///
+    /// See [Client::subscribe_account] for more information.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let mut logs = client.subscribe_logs(turnstile::ID).await?;
+    /// turnstile_instruction::push(&client, /* ... */).await?;
+    /// let logs = tokio::time::timeout(Duration::from_secs(5), logs.next()).await?.unwrap();
+    /// assert!(logs.logs.contains(&"Program log: Instruction: Push".to_string()));
+    /// ```
+    #[throws]
+    pub async fn subscribe_logs(&self, program_id: Pubkey) -> BoxStream<'_, RpcLogsResponse> {
+        self.backend.subscribe_logs(&program_id).await?
+    }
+
+    /// Decodes all events of the chosen type emitted during the transaction.
+    ///
+    /// It's a shorthand for [TransactionOutcome::events].
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let outcome = escrow_instruction::exchange(&client, /* ... */).await?;
+    /// let exchanged = client.events::<escrow::Exchanged>(&outcome)?;
+    /// ```
+    #[throws]
+    pub fn events<E: Event>(&self, outcome: &TransactionOutcome) -> Vec<E> {
+        outcome.events::<E>()?
     }
 
     /// Sends the Anchor instruction with associated accounts and signers.
     ///
+    /// The consumed compute units are recorded for `trdelnik test --check-cu`,
+    /// see [Commander::run_tests_with_cu_check](crate::Commander::run_tests_with_cu_check).
+    ///
+    /// # Errors
+    ///
+    /// It fails when:
+    /// - the Solana cluster is not running.
+    /// - the transaction is rejected. The error carries the [TransactionError](solana_sdk::transaction::TransactionError)
+    ///   and the simulation logs, see [transaction_error](crate::error_reporter::transaction_error),
+    ///   [transaction_logs](crate::error_reporter::transaction_logs)
+    ///   and [custom_program_error](crate::error_reporter::custom_program_error).
+    ///
     /// # Example
     ///
     /// ```rust,ignore
@@ -190,7 +632,7 @@ impl Client {
     ///     user: Pubkey,
     ///     system_program: Pubkey,
     ///     signers: impl IntoIterator<Item = Keypair> + Send + 'static,
-    /// ) -> Result<EncodedConfirmedTransactionWithStatusMeta, ClientError> {
+    /// ) -> Result<TransactionOutcome, ClientError> {
     ///     Ok(client
     ///         .send_instruction(
     ///             PROGRAM_ID,
@@ -212,31 +654,56 @@ impl Client {
         instruction: impl InstructionData + Send + 'static,
         accounts: impl ToAccountMetas + Send + 'static,
         signers: impl IntoIterator<Item = Keypair> + Send + 'static,
-    ) -> EncodedConfirmedTransactionWithStatusMeta {
-        let program = self.anchor_client.program(program).unwrap();
-        let mut request = program.request().args(instruction).accounts(accounts);
+    ) -> TransactionOutcome {
+        self.send_instruction_with_options(
+            program,
+            instruction,
+            accounts,
+            signers,
+            &self.send_options,
+        )
+        .await?
+    }
+
+    /// Sends the Anchor instruction with associated accounts and signers using the chosen [SendOptions].
+    ///
+    /// See [Client::send_instruction] for more information.
+    #[throws]
+    pub async fn send_instruction_with_options(
+        &self,
+        program: Pubkey,
+        instruction: impl InstructionData + Send + 'static,
+        accounts: impl ToAccountMetas + Send + 'static,
+        signers: impl IntoIterator<Item = Keypair> + Send + 'static,
+        options: &SendOptions,
+    ) -> TransactionOutcome {
+        let instruction_name = instruction_name(&instruction);
+        let instruction = Instruction {
+            program_id: program,
+            accounts: accounts.to_account_metas(None),
+            data: instruction.data(),
+        };
         let signers = signers.into_iter().collect::<Vec<_>>();
-        for signer in &signers {
-            request = request.signer(signer);
+        let outcome = self
+            .send_transaction_with_options(&[instruction], &signers, options)
+            .await?;
+        if let Some(units) = outcome.compute_units_consumed {
+            record_compute_units(&instruction_name, units);
         }
-        let signature = request.send().await.unwrap();
-
-        let rpc_client = self.anchor_client.program(System::id())?.async_rpc();
-        rpc_client
-            .get_transaction_with_config(
-                &signature,
-                RpcTransactionConfig {
-                    encoding: Some(UiTransactionEncoding::Binary),
-                    commitment: Some(CommitmentConfig::confirmed()),
-                    max_supported_transaction_version: None,
-                },
-            )
-            .await
-            .unwrap()
+        outcome
     }
 
     /// Sends the transaction with associated instructions and signers.
     ///
+    /// # Errors
+    ///
+    /// It fails when:
+    /// - the Solana cluster is not running.
+    /// - the transaction is rejected. The error carries the [TransactionError](solana_sdk::transaction::TransactionError)
+    ///   and the simulation logs, see [transaction_error](crate::error_reporter::transaction_error),
+    ///   [transaction_logs](crate::error_reporter::transaction_logs)
+    ///   and [custom_program_error](crate::error_reporter::custom_program_error).
+    ///
     /// # Example
     ///
     /// ```rust,ignore
@@ -247,7 +714,7 @@ impl Client {
     ///     lamports: u64,
     ///     space: u64,
     ///     owner: &Pubkey,
-    /// ) -> EncodedConfirmedTransaction {
+    /// ) -> TransactionOutcome {
     ///     self.send_transaction(
     ///         &[system_instruction::create_account(
     ///             &self.payer().pubkey(),
@@ -266,396 +733,1759 @@ impl Client {
         &self,
         instructions: &[Instruction],
         signers: impl IntoIterator<Item = &Keypair> + Send,
-    ) -> EncodedConfirmedTransactionWithStatusMeta {
-        let rpc_client = self
-            .anchor_client
-            .program(System::id())
-            .unwrap()
-            .async_rpc();
+    ) -> TransactionOutcome {
+        self.send_transaction_with_options(instructions, signers, &self.send_options)
+            .await?
+    }
+
+    /// Sends the transaction with associated instructions and signers using the chosen [SendOptions].
+    ///
+    /// The compute budget instructions requested by the options are put in front of the instructions.
+    /// See [Client::send_transaction] for more information.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let options = SendOptions {
+    ///     compute_unit_limit: Some(50_000),
+    ///     ..SendOptions::default()
+    /// };
+    /// client.send_transaction_with_options(&[heavy_ix], [], &options).await?;
+    /// ```
+    #[throws]
+    pub async fn send_transaction_with_options(
+        &self,
+        instructions: &[Instruction],
+        signers: impl IntoIterator<Item = &Keypair> + Send,
+        options: &SendOptions,
+    ) -> TransactionOutcome {
         let mut signers = signers.into_iter().collect::<Vec<_>>();
         signers.push(self.payer());
 
-        let tx = &Transaction::new_signed_with_payer(
-            instructions,
+        let mut all_instructions = options.compute_budget_instructions();
+        all_instructions.extend_from_slice(instructions);
+
+        let tx = Transaction::new_signed_with_payer(
+            &all_instructions,
             Some(&self.payer.pubkey()),
             &signers,
-            rpc_client.get_latest_blockhash().await.unwrap(),
+            self.backend.get_latest_blockhash().await?,
         );
-        // @TODO make this call async with task::spawn_blocking
-        let signature = rpc_client.send_and_confirm_transaction(tx).await.unwrap();
-        let transaction = rpc_client
-            .get_transaction_with_config(
-                &signature,
-                RpcTransactionConfig {
-                    encoding: Some(UiTransactionEncoding::Binary),
-                    commitment: Some(CommitmentConfig::confirmed()),
-                    max_supported_transaction_version: None,
-                },
-            )
-            .await
-            .unwrap();
-
-        transaction
+        TransactionOutcome::try_from(self.backend.send_transaction(&tx.into(), options).await?)?
     }
 
-    /// Airdrops lamports to the chosen account.
-    #[throws]
-    pub async fn airdrop(&self, address: Pubkey, lamports: u64) {
-        let rpc_client = self
-            .anchor_client
-            .program(System::id())
-            .unwrap()
-            .async_rpc();
-
-        let signature = rpc_client
-            .request_airdrop(&address, lamports)
+    /// Signs and sends the batch of transactions concurrently, at most `max_concurrency` requests at once,
+    /// and waits for their confirmation, e.g. to set up fixtures for many users or to load-test
+    /// a program for the write-lock contention.
+    ///
+    /// Every item is a transaction with the instructions and signers; the payer signs all of them.
+    /// All transactions are submitted before the confirmation starts, then they are confirmed
+    /// together, so the batch waits for about one confirmation instead of one per transaction.
+    /// The outcomes are returned in the order of the batch, the failed transactions
+    /// do not stop the others.
+    ///
+    /// _Note_: The transactions share the blockhash, so the ones with the same instructions and signers
+    /// would have the same signature, make them unique, e.g. by a memo instruction.
+    /// The `ProgramTest` backend processes the transactions one by one.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let outcomes = client
+    ///     .send_many(
+    ///         users
+    ///             .iter()
+    ///             .map(|user| (vec![transfer(&payer, &user.pubkey(), LAMPORTS_PER_SOL)], vec![]))
+    ///             .collect(),
+    ///         8,
+    ///     )
+    ///     .await;
+    /// assert!(outcomes.iter().all(Result::is_ok));
+    /// ```
+    pub async fn send_many(
+        &self,
+        transactions: Vec<(Vec<Instruction>, Vec<&Keypair>)>,
+        max_concurrency: usize,
+    ) -> Vec<Result<TransactionOutcome, Error>> {
+        self.send_many_with_options(transactions, max_concurrency, &self.send_options)
             .await
-            .unwrap();
-
-        let (airdrop_result, error) = loop {
-            match rpc_client.get_signature_status(&signature).await.unwrap() {
-                Some(Ok(_)) => {
-                    debug!("{} lamports airdropped", lamports);
-                    break (true, None);
-                }
-                Some(Err(transaction_error)) => break (false, Some(transaction_error)),
-                None => sleep(Duration::from_millis(500)),
-            }
-        };
-        if !airdrop_result {
-            throw!(Error::SolanaClientError(error.unwrap().into()));
-        }
     }
 
-    /// Get balance of an account
-    #[throws]
-    pub async fn get_balance(&mut self, address: &Pubkey) -> u64 {
-        let rpc_client = self.anchor_client.program(System::id())?.async_rpc();
-        rpc_client.get_balance(address).await?
+    /// Sends the batch of transactions concurrently with the options, see [Client::send_many].
+    pub async fn send_many_with_options(
+        &self,
+        transactions: Vec<(Vec<Instruction>, Vec<&Keypair>)>,
+        max_concurrency: usize,
+        options: &SendOptions,
+    ) -> Vec<Result<TransactionOutcome, Error>> {
+        let blockhash = match self.backend.get_latest_blockhash().await {
+            Ok(blockhash) => blockhash,
+            // the error is not `Clone`, every transaction gets its message
+            Err(error) => {
+                return transactions
+                    .iter()
+                    .map(|_| {
+                        Err(Error::SolanaClientError(
+                            ClientErrorKind::Custom(error.to_string()).into(),
+                        ))
+                    })
+                    .collect()
+            }
+        };
+        let transactions = transactions
+            .into_iter()
+            .map(|(instructions, mut signers)| {
+                signers.push(self.payer());
+                let mut all_instructions = options.compute_budget_instructions();
+                all_instructions.extend(instructions);
+                Transaction::new_signed_with_payer(
+                    &all_instructions,
+                    Some(&self.payer.pubkey()),
+                    &signers,
+                    blockhash,
+                )
+                .into()
+            })
+            .collect::<Vec<_>>();
+        self.backend
+            .send_transactions(&transactions, max_concurrency, options)
+            .await
+            .into_iter()
+            .map(|transaction| TransactionOutcome::try_from(transaction?))
+            .collect()
     }
 
-    /// Get token balance of an token account
+    /// Simulates the Anchor instruction with associated accounts and signers without committing it.
+    ///
+    /// The state of the `post_accounts` after the instruction is returned in [SimulationOutcome::accounts].
+    /// See [Client::simulate_transaction] for more information.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let simulation = client
+    ///     .simulate_instruction(
+    ///         turnstile::ID,
+    ///         turnstile::instruction::Coin { dummy_arg: String::new() },
+    ///         turnstile::accounts::UpdateState { state: state.pubkey() },
+    ///         [],
+    ///         &[state.pubkey()],
+    ///     )
+    ///     .await?;
+    /// let state: turnstile::State = simulation.account_data(&state.pubkey())?;
+    /// assert!(!state.locked);
+    /// ```
+    #[throws]
+    pub async fn simulate_instruction(
+        &self,
+        program: Pubkey,
+        instruction: impl InstructionData + Send + 'static,
+        accounts: impl ToAccountMetas + Send + 'static,
+        signers: impl IntoIterator<Item = Keypair> + Send + 'static,
+        post_accounts: &[Pubkey],
+    ) -> SimulationOutcome {
+        let instruction = Instruction {
+            program_id: program,
+            accounts: accounts.to_account_metas(None),
+            data: instruction.data(),
+        };
+        let signers = signers.into_iter().collect::<Vec<_>>();
+        self.simulate_transaction(&[instruction], &signers, post_accounts)
+            .await?
+    }
+
+    /// Simulates the transaction with associated instructions and signers without committing it.
+    ///
+    /// The simulation runs against the current state, the [SendOptions] of the client are applied.
+    /// A failed transaction is not an error, it is reported in [SimulationOutcome::error]
+    /// together with the logs, so the error paths can be checked without polluting the ledger.
+    /// The state of the `post_accounts` after the transaction is returned in [SimulationOutcome::accounts].
+    ///
+    /// _Note_: `BanksClient` does not return the accounts after the simulation,
+    /// so the `post_accounts` are supported only by the RPC backend (e.g. the localnet).
+    ///
+    /// # Errors
+    ///
+    /// It fails when:
+    /// - the Solana cluster is not running or the simulation result cannot be decoded.
+    /// - `post_accounts` are requested from the `ProgramTest` backend.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let simulation = client
+    ///     .simulate_transaction(&[withdraw_ix], [&alice], &[vault])
+    ///     .await?;
+    /// assert!(simulation.is_ok());
+    /// assert_eq!(simulation.account(&vault).unwrap().lamports, 0);
+    /// ```
+    #[throws]
+    pub async fn simulate_transaction(
+        &self,
+        instructions: &[Instruction],
+        signers: impl IntoIterator<Item = &Keypair> + Send,
+        post_accounts: &[Pubkey],
+    ) -> SimulationOutcome {
+        let mut signers = signers.into_iter().collect::<Vec<_>>();
+        signers.push(self.payer());
+
+        let mut all_instructions = self.send_options.compute_budget_instructions();
+        all_instructions.extend_from_slice(instructions);
+
+        let tx = Transaction::new_signed_with_payer(
+            &all_instructions,
+            Some(&self.payer.pubkey()),
+            &signers,
+            self.backend.get_latest_blockhash().await?,
+        );
+        SimulationOutcome::new(
+            self.backend
+                .simulate_transaction(&tx.into(), post_accounts)
+                .await?,
+            post_accounts,
+        )?
+    }
+
+    /// Sends the instructions in a `v0` transaction compiled against the address lookup tables.
+    ///
+    /// The accounts found in the lookup tables are loaded from them instead of being stored
+    /// in the transaction, so the transaction can reference more accounts than a legacy one.
+    /// The payer and the signers are added to the transaction directly.
+    /// The [SendOptions] of the client are applied.
+    ///
+    /// # Errors
+    ///
+    /// It fails when:
+    /// - a lookup table does not exist.
+    /// - the message cannot be compiled or signed, e.g. a signer is missing.
+    /// - the transaction is rejected, see [Client::send_transaction].
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let table = client.create_lookup_table(client.payer()).await?;
+    /// client.extend_lookup_table(table, client.payer(), market_accounts).await?;
+    /// client
+    ///     .send_versioned_transaction(&[settle_ix], [&taker], &[table])
+    ///     .await?;
+    /// ```
+    #[throws]
+    pub async fn send_versioned_transaction(
+        &self,
+        instructions: &[Instruction],
+        signers: impl IntoIterator<Item = &Keypair> + Send,
+        lookup_tables: &[Pubkey],
+    ) -> TransactionOutcome {
+        let mut lookup_table_accounts = Vec::with_capacity(lookup_tables.len());
+        for lookup_table in lookup_tables {
+            lookup_table_accounts.push(self.get_address_lookup_table(*lookup_table).await?);
+        }
+
+        let mut all_instructions = self.send_options.compute_budget_instructions();
+        all_instructions.extend_from_slice(instructions);
+
+        let message = v0::Message::try_compile(
+            &self.payer.pubkey(),
+            &all_instructions,
+            &lookup_table_accounts,
+            self.backend.get_latest_blockhash().await?,
+        )
+        .map_err(|error| {
+            Error::SolanaClientError(ClientErrorKind::Custom(error.to_string()).into())
+        })?;
+
+        // `VersionedTransaction` requires exactly one keypair for every signature
+        let mut signers = signers.into_iter().collect::<Vec<_>>();
+        signers.insert(0, self.payer());
+        let mut unique_signers: Vec<&Keypair> = Vec::with_capacity(signers.len());
+        for signer in signers {
+            if !unique_signers
+                .iter()
+                .any(|unique| unique.pubkey() == signer.pubkey())
+            {
+                unique_signers.push(signer);
+            }
+        }
+
+        let tx = VersionedTransaction::try_new(VersionedMessage::V0(message), &unique_signers)
+            .map_err(|error| {
+                Error::SolanaClientError(ClientErrorKind::SigningError(error).into())
+            })?;
+        TransactionOutcome::try_from(
+            self.backend
+                .send_transaction(&tx, &self.send_options)
+                .await?,
+        )?
+    }
+
+    /// Creates an address lookup table owned by `authority` and returns its address.
+    ///
+    /// The function returns after the slot the table was created in,
+    /// because the table address is derived from a recent slot.
+    #[throws]
+    pub async fn create_lookup_table(&self, authority: &Keypair) -> Pubkey {
+        let recent_slot = self.backend.get_slot().await?;
+        // the derivation slot has to be present in the `SlotHashes` sysvar
+        self.backend.wait_for_next_slot().await?;
+
+        let (create_ix, lookup_table) = address_lookup_table_instruction::create_lookup_table(
+            authority.pubkey(),
+            self.payer.pubkey(),
+            recent_slot,
+        );
+        self.send_transaction(&[create_ix], [authority]).await?;
+        lookup_table
+    }
+
+    /// Adds the addresses to the address lookup table; the payer pays for the table reallocation.
+    ///
+    /// About 30 addresses fit into one transaction. The function returns after the slot
+    /// the table was extended in, because the new addresses cannot be used sooner.
+    #[throws]
+    pub async fn extend_lookup_table(
+        &self,
+        lookup_table: Pubkey,
+        authority: &Keypair,
+        addresses: Vec<Pubkey>,
+    ) -> TransactionOutcome {
+        let outcome = self
+            .send_transaction(
+                &[address_lookup_table_instruction::extend_lookup_table(
+                    lookup_table,
+                    authority.pubkey(),
+                    Some(self.payer.pubkey()),
+                    addresses,
+                )],
+                [authority],
+            )
+            .await?;
+        self.backend.wait_for_next_slot().await?;
+        outcome
+    }
+
+    /// Deactivates the address lookup table, so it cannot be extended or used anymore.
+    ///
+    /// The table can be closed once the deactivation slot is no longer recent (after about 513 slots).
+    #[throws]
+    pub async fn deactivate_lookup_table(
+        &self,
+        lookup_table: Pubkey,
+        authority: &Keypair,
+    ) -> TransactionOutcome {
+        self.send_transaction(
+            &[address_lookup_table_instruction::deactivate_lookup_table(
+                lookup_table,
+                authority.pubkey(),
+            )],
+            [authority],
+        )
+        .await?
+    }
+
+    /// Closes the deactivated address lookup table and transfers its lamports to `recipient`.
+    #[throws]
+    pub async fn close_lookup_table(
+        &self,
+        lookup_table: Pubkey,
+        authority: &Keypair,
+        recipient: Pubkey,
+    ) -> TransactionOutcome {
+        self.send_transaction(
+            &[address_lookup_table_instruction::close_lookup_table(
+                lookup_table,
+                authority.pubkey(),
+                recipient,
+            )],
+            [authority],
+        )
+        .await?
+    }
+
+    /// Gets the addresses stored in the address lookup table.
+    ///
+    /// # Errors
+    ///
+    /// It fails when:
+    /// - the account does not exist.
+    /// - the Solana cluster is not running.
+    /// - the account is not an address lookup table.
+    #[throws]
+    pub async fn get_address_lookup_table(
+        &self,
+        lookup_table: Pubkey,
+    ) -> AddressLookupTableAccount {
+        self.backend.get_address_lookup_table(&lookup_table).await?
+    }
+
+    /// Creates a durable nonce account controlled by the `authority`.
+    ///
+    /// The function returns after the slot the nonce was created in,
+    /// because the nonce cannot be used in the same slot.
+    #[throws]
+    pub async fn create_nonce_account(
+        &self,
+        nonce: &Keypair,
+        authority: Pubkey,
+    ) -> TransactionOutcome {
+        let outcome = self
+            .send_transaction(
+                &system_instruction::create_nonce_account(
+                    &self.payer.pubkey(),
+                    &nonce.pubkey(),
+                    &authority,
+                    self.backend
+                        .get_minimum_balance_for_rent_exemption(NonceState::size())
+                        .await?,
+                ),
+                [nonce],
+            )
+            .await?;
+        self.backend.wait_for_next_slot().await?;
+        outcome
+    }
+
+    /// Advances the durable nonce, so the transactions built on the current one are invalidated.
+    ///
+    /// The function returns after the slot the nonce was advanced in.
+    #[throws]
+    pub async fn advance_nonce_account(
+        &self,
+        nonce: Pubkey,
+        authority: &Keypair,
+    ) -> TransactionOutcome {
+        let outcome = self
+            .send_transaction(
+                &[system_instruction::advance_nonce_account(
+                    &nonce,
+                    &authority.pubkey(),
+                )],
+                [authority],
+            )
+            .await?;
+        self.backend.wait_for_next_slot().await?;
+        outcome
+    }
+
+    /// Gets the durable nonce, its authority and fee calculator.
+    ///
+    /// # Errors
+    ///
+    /// It fails when the account does not exist or it is not an initialized nonce account.
+    #[throws]
+    pub async fn get_nonce_data(&self, nonce: Pubkey) -> NonceData {
+        let account = self
+            .get_account(nonce)
+            .await?
+            .ok_or(Error::AccountNotFound)?;
+        let versions: NonceVersions = bincode::deserialize(&account.data).map_err(|error| {
+            Error::SolanaClientError(ClientErrorKind::Custom(error.to_string()).into())
+        })?;
+        match versions.state() {
+            NonceState::Initialized(data) => data.clone(),
+            NonceState::Uninitialized => throw!(Error::SolanaClientError(
+                ClientErrorKind::Custom(format!("nonce account {nonce} is not initialized")).into()
+            )),
+        }
+    }
+
+    /// Builds the unsigned transaction on the durable nonce, so it does not expire
+    /// while the offline signers sign it.
+    ///
+    /// The instruction advancing the nonce is prepended to the `instructions`,
+    /// the `nonce_authority` has to sign the transaction.
+    /// See [OfflineTransaction] for signing and [Client::send_offline_transaction] for submitting it.
+    #[throws]
+    pub async fn nonce_transaction(
+        &self,
+        instructions: &[Instruction],
+        nonce: Pubkey,
+        nonce_authority: Pubkey,
+        fee_payer: Pubkey,
+    ) -> OfflineTransaction {
+        let nonce_data = self.get_nonce_data(nonce).await?;
+        let mut message = Message::new_with_nonce(
+            instructions.to_vec(),
+            Some(&fee_payer),
+            &nonce,
+            &nonce_authority,
+        );
+        message.recent_blockhash = nonce_data.blockhash();
+        OfflineTransaction::new(Transaction::new_unsigned(message))
+    }
+
+    /// Submits the transaction signed by all required signers.
+    ///
+    /// # Errors
+    ///
+    /// It fails when a signature is missing or the transaction fails.
+    #[throws]
+    pub async fn send_offline_transaction(
+        &self,
+        transaction: &OfflineTransaction,
+    ) -> TransactionOutcome {
+        let missing_signers = transaction.missing_signers();
+        if !missing_signers.is_empty() {
+            throw!(Error::SolanaClientError(
+                ClientErrorKind::Custom(format!(
+                    "the transaction is missing the signatures of {missing_signers:?}"
+                ))
+                .into()
+            ));
+        }
+        TransactionOutcome::try_from(
+            self.backend
+                .send_transaction(
+                    &transaction.transaction().clone().into(),
+                    &self.send_options,
+                )
+                .await?,
+        )?
+    }
+
+    /// Airdrops lamports to the chosen account.
+    #[throws]
+    pub async fn airdrop(&self, address: Pubkey, lamports: u64) {
+        self.backend.airdrop(&address, lamports).await?
+    }
+
+    /// Get balance of an account
+    #[throws]
+    pub async fn get_balance(&mut self, address: &Pubkey) -> u64 {
+        self.backend.get_balance(address).await?
+    }
+
+    /// Get token balance of an token account
+    #[throws]
+    pub async fn get_token_balance(&mut self, address: Pubkey) -> UiTokenAmount {
+        self.backend.get_token_balance(&address).await?
+    }
+
+    /// Warps the cluster to the `slot`.
+    ///
+    /// _Note_: The localnet cannot skip slots, the `Rpc` backend waits until the `slot` is reached.
+    ///
+    /// # Errors
+    ///
+    /// It fails when the `slot` has already passed.
+    #[throws]
+    pub async fn warp_to_slot(&self, slot: Slot) {
+        self.backend.warp_to_slot(slot).await?
+    }
+
+    /// Sets `Clock::unix_timestamp` seen by the programs.
+    ///
+    /// _Note_: The localnet clock cannot be changed, the `Rpc` backend waits until the `unix_timestamp` is reached.
+    /// The `ProgramTest` clock is recalculated when the slot is warped.
+    ///
+    /// # Errors
+    ///
+    /// It fails with the `Rpc` backend when the `unix_timestamp` has already passed.
+    #[throws]
+    pub async fn set_clock(&self, unix_timestamp: UnixTimestamp) {
+        self.backend.set_clock(unix_timestamp).await?
+    }
+
+    /// Moves `Clock::unix_timestamp` forward by the `duration`, e.g. to let a vesting period pass.
+    ///
+    /// The `ProgramTest` backend warps by the slots the `duration` takes,
+    /// the `Rpc` backend waits for them.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// client.advance_time(Duration::from_secs(24 * 60 * 60)).await?;
+    /// vesting_instruction::claim(&client, /* ... */).await?;
+    /// ```
+    #[throws]
+    pub async fn advance_time(&self, duration: Duration) {
+        self.backend.advance_time(duration).await?
+    }
+
+    /// Gets the `Clock` sysvar.
+    #[throws]
+    pub async fn get_clock(&self) -> Clock {
+        self.backend.get_sysvar().await?
+    }
+
+    /// Gets the `Rent` sysvar.
+    #[throws]
+    pub async fn get_rent(&self) -> Rent {
+        self.backend.get_sysvar().await?
+    }
+
+    /// Gets the `EpochSchedule` sysvar.
+    #[throws]
+    pub async fn get_epoch_schedule(&self) -> EpochSchedule {
+        self.backend.get_sysvar().await?
+    }
+
+    /// Deploys a program based on it's name.
+    /// This function wraps boilerplate code required for the successful deployment of a program,
+    /// i.e. SOLs airdrop etc.
+    ///
+    /// # Arguments
+    ///
+    /// * `program_keypair` - [Keypair] used for the program
+    /// * `program_name` - Name of the program to be deployed
+    ///
+    /// # Example:
+    ///
+    /// *Project structure*
+    ///
+    /// ```text
+    /// project/
+    /// - programs/
+    ///   - awesome_contract/
+    ///     - ...
+    ///     - Cargo.toml
+    ///   - turnstile/
+    ///     - ...
+    ///     - Cargo.toml
+    /// - ...
+    /// - Cargo.toml
+    /// ```
+    ///
+    /// *Code*
+    ///
+    /// ```rust,ignore
+    /// client.deploy_program(program_keypair(0), "awesome_contract");
+    /// client.deploy_program(program_keypair(1), "turnstile");
+    /// ```
+    ///
+    /// The program is deployed with the upgradeable BPF loader and the payer as its upgrade authority,
+    /// see [Client::deploy_by_name_with_authority] to choose another one.
+    #[throws]
+    pub async fn deploy_by_name(&self, program_keypair: &Keypair, program_name: &str) {
+        self.deploy_by_name_with_authority(program_keypair, program_name, self.payer())
+            .await?
+    }
+
+    /// Deploys a program based on it's name with the chosen upgrade authority.
+    ///
+    /// The program data account is allocated twice as large as the program to leave room for upgrades.
+    /// See [Client::deploy_by_name] for more information.
+    #[throws]
+    pub async fn deploy_by_name_with_authority(
+        &self,
+        program_keypair: &Keypair,
+        program_name: &str,
+        upgrade_authority: &Keypair,
+    ) {
+        debug!("reading program data");
+
+        let reader = Reader::new();
+
+        let program_data =
+            reader
+                .program_data(program_name)
+                .await
+                .map_err(|error| match error {
+                    ReaderError::Io(error) => Error::IOError(error),
+                    error => Error::IOError(io::Error::new(io::ErrorKind::InvalidData, error)),
+                })?;
+
+        debug!("airdropping the missing balance required to deploy the program");
+
+        let max_data_len = program_data.len() * 2;
+        let deploy_cost = self.deploy_cost(program_data.len(), max_data_len).await?;
+        let balance = self.backend.get_balance(&self.payer().pubkey()).await?;
+        if deploy_cost > balance {
+            // TODO: This will fail on devnet where airdrops are limited to 1 SOL
+            self.airdrop(self.payer().pubkey(), deploy_cost - balance)
+                .await?;
+        }
+
+        debug!("deploying program");
+
+        self.deploy(
+            program_keypair,
+            &program_data,
+            upgrade_authority,
+            max_data_len,
+        )
+        .await?;
+
+        debug!("program deployed succefully");
+    }
+
+    /// Returns the lamports the payer needs to [deploy](Client::deploy) the program of the `program_len` bytes
+    /// with the `max_data_len`.
+    ///
+    /// It covers the rent exemption of the buffer, program and program data accounts
+    /// and the fees of the buffer writes and of the deployment at the default target fee per signature.
+    /// The loader transactions have no priority fee, see [Client::deploy].
+    #[throws]
+    pub async fn deploy_cost(&self, program_len: usize, max_data_len: usize) -> u64 {
+        let mut lamports = 0;
+        for len in [
+            UpgradeableLoaderState::size_of_buffer(program_len),
+            UpgradeableLoaderState::size_of_program(),
+            UpgradeableLoaderState::size_of_programdata(max_data_len),
+        ] {
+            lamports += self
+                .backend
+                .get_minimum_balance_for_rent_exemption(len)
+                .await?;
+        }
+        // the buffer creation, the writes and the deployment, each signed by up to three signers
+        let writes = (program_len + PROGRAM_DATA_CHUNK_SIZE - 1) / PROGRAM_DATA_CHUNK_SIZE;
+        let transactions = writes as u64 + 2;
+        lamports + transactions * 3 * DEFAULT_TARGET_LAMPORTS_PER_SIGNATURE
+    }
+
+    /// Deploys the program with the upgradeable BPF loader.
+    ///
+    /// The program data are written to a new buffer account first and then deployed
+    /// with the `DeployWithMaxDataLen` instruction.
+    ///
+    /// # Arguments
+    ///
+    /// * `program_keypair` - [Keypair] used for the program
+    /// * `program_data` - the program binary (`.so`)
+    /// * `upgrade_authority` - the authority allowed to upgrade and close the program
+    /// * `max_data_len` - the maximum length of the program binary, i.e. of its upgrades
+    ///
+    /// The function returns after the slot the program was deployed in,
+    /// so the program can be invoked right away.
+    ///
+    /// _Note_: The loader transactions (also of [Client::upgrade_program], [Client::set_upgrade_authority]
+    /// and [Client::close_program]) are sent without the compute budget instructions of the client [SendOptions],
+    /// the buffer writes would not fit into a transaction with them.
+    #[throws]
+    pub async fn deploy(
+        &self,
+        program_keypair: &Keypair,
+        program_data: &[u8],
+        upgrade_authority: &Keypair,
+        max_data_len: usize,
+    ) -> TransactionOutcome {
+        debug!("program_data_len: {}", program_data.len());
+
+        let buffer = self.write_buffer(program_data, upgrade_authority).await?;
+
+        debug!("deploy program");
+
+        let program_lamports = self
+            .backend
+            .get_minimum_balance_for_rent_exemption(UpgradeableLoaderState::size_of_program())
+            .await?;
+        let deploy_ixs = bpf_loader_upgradeable::deploy_with_max_program_len(
+            &self.payer.pubkey(),
+            &program_keypair.pubkey(),
+            &buffer,
+            &upgrade_authority.pubkey(),
+            program_lamports,
+            max_data_len,
+        )
+        .map_err(loader_error)?;
+        let outcome = self
+            .send_loader_transaction(&deploy_ixs, [program_keypair, upgrade_authority])
+            .await?;
+
+        // the deployed program can be invoked from the next slot
+        self.backend.wait_for_next_slot().await?;
+
+        debug!("program deployed");
+        outcome
+    }
+
+    /// Upgrades the deployed program to the new program binary.
+    ///
+    /// The new binary cannot be larger than the `max_data_len` the program was deployed with.
+    #[throws]
+    pub async fn upgrade_program(
+        &self,
+        program_id: Pubkey,
+        program_data: &[u8],
+        upgrade_authority: &Keypair,
+    ) -> TransactionOutcome {
+        let buffer = self.write_buffer(program_data, upgrade_authority).await?;
+
+        debug!("upgrade program");
+
+        let outcome = self
+            .send_loader_transaction(
+                &[bpf_loader_upgradeable::upgrade(
+                    &program_id,
+                    &buffer,
+                    &upgrade_authority.pubkey(),
+                    &self.payer.pubkey(),
+                )],
+                [upgrade_authority],
+            )
+            .await?;
+
+        // the upgraded program can be invoked from the next slot
+        self.backend.wait_for_next_slot().await?;
+
+        debug!("program upgraded");
+        outcome
+    }
+
+    /// Sets the new upgrade authority of the program.
+    ///
+    /// The program becomes immutable when `new_upgrade_authority` is `None`.
+    #[throws]
+    pub async fn set_upgrade_authority(
+        &self,
+        program_id: Pubkey,
+        upgrade_authority: &Keypair,
+        new_upgrade_authority: Option<Pubkey>,
+    ) -> TransactionOutcome {
+        self.send_loader_transaction(
+            &[bpf_loader_upgradeable::set_upgrade_authority(
+                &program_id,
+                &upgrade_authority.pubkey(),
+                new_upgrade_authority.as_ref(),
+            )],
+            [upgrade_authority],
+        )
+        .await?
+    }
+
+    /// Closes the program and transfers the lamports of its program data account to `recipient`.
+    ///
+    /// The closed program cannot be invoked or redeployed to the same address anymore.
+    #[throws]
+    pub async fn close_program(
+        &self,
+        program_id: Pubkey,
+        upgrade_authority: &Keypair,
+        recipient: Pubkey,
+    ) -> TransactionOutcome {
+        let (program_data_address, _) =
+            Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id());
+        let outcome = self
+            .send_loader_transaction(
+                &[bpf_loader_upgradeable::close_any(
+                    &program_data_address,
+                    &recipient,
+                    Some(&upgrade_authority.pubkey()),
+                    Some(&program_id),
+                )],
+                [upgrade_authority],
+            )
+            .await?;
+
+        // the program is closed from the next slot
+        self.backend.wait_for_next_slot().await?;
+
+        outcome
+    }
+
+    /// Checks that the accounts created by the old program binary are still deserializable
+    /// after the program is upgraded to the new binary.
+    ///
+    /// 1. The old binary is deployed with the payer as the upgrade authority.
+    /// 2. The `setup` future is awaited to create the program accounts, e.g. by calling the program instructions.
+    /// 3. The program is upgraded in place to the new binary.
+    /// 4. All program accounts are deserialized as the matching [AccountTypes]
+    ///    (the way [Client::account_data] does) and the failures are reported.
+    ///
+    /// _Note_: The `ProgramTest` backend only finds the program accounts referenced by the transactions
+    /// sent through this client.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let reader = Reader::new();
+    /// let old_program_data = reader.program_data_at_revision("escrow", "v1.0.0").await?;
+    /// let new_program_data = reader.program_data("escrow").await?;
+    ///
+    /// let report = client
+    ///     .check_upgrade(
+    ///         &program_keypair(0),
+    ///         &old_program_data,
+    ///         &new_program_data,
+    ///         &AccountTypes::new().with::<escrow::EscrowAccount>(),
+    ///         async {
+    ///             escrow_instruction::initialize_escrow(&client, /* ... */).await?;
+    ///             Ok(())
+    ///         },
+    ///     )
+    ///     .await?;
+    /// assert!(report.is_ok(), "{report}");
+    /// ```
+    #[throws]
+    pub async fn check_upgrade(
+        &self,
+        program_keypair: &Keypair,
+        old_program_data: &[u8],
+        new_program_data: &[u8],
+        account_types: &AccountTypes,
+        setup: impl Future<Output = Result<(), Error>>,
+    ) -> UpgradeReport {
+        let program_id = program_keypair.pubkey();
+        let max_data_len = old_program_data.len().max(new_program_data.len());
+
+        debug!("deploying the old program");
+        self.deploy(
+            program_keypair,
+            old_program_data,
+            self.payer(),
+            max_data_len,
+        )
+        .await?;
+
+        debug!("setting up the program accounts");
+        setup.await?;
+
+        debug!("upgrading the program");
+        self.upgrade_program(program_id, new_program_data, self.payer())
+            .await?;
+
+        let mut report = UpgradeReport::default();
+        for (address, account) in self
+            .backend
+            .get_program_accounts(&program_id, Vec::new())
+            .await?
+        {
+            match account_types.decode(&account.data) {
+                Some((account_type, Ok(()))) => report.decoded.push((address, account_type)),
+                Some((account_type, Err(error))) => report.failures.push(AccountDecodeFailure {
+                    address,
+                    account_type,
+                    error,
+                }),
+                None => report.unknown.push(address),
+            }
+        }
+        report
+    }
+
+    /// Creates a buffer account owned by `authority` and writes the program data into it.
+    #[throws]
+    async fn write_buffer(&self, program_data: &[u8], authority: &Keypair) -> Pubkey {
+        debug!("create buffer account");
+
+        let buffer = Keypair::new();
+        let buffer_lamports = self
+            .backend
+            .get_minimum_balance_for_rent_exemption(UpgradeableLoaderState::size_of_buffer(
+                program_data.len(),
+            ))
+            .await?;
+        let create_buffer_ixs = bpf_loader_upgradeable::create_buffer(
+            &self.payer.pubkey(),
+            &buffer.pubkey(),
+            &authority.pubkey(),
+            buffer_lamports,
+            program_data.len(),
+        )
+        .map_err(loader_error)?;
+        self.send_loader_transaction(&create_buffer_ixs, [&buffer])
+            .await?;
+
+        debug!("write program data");
+
+        let mut offset = 0usize;
+        let mut futures_vec = Vec::new();
+
+        for chunk in program_data.chunks(PROGRAM_DATA_CHUNK_SIZE) {
+            let write_ix = bpf_loader_upgradeable::write(
+                &buffer.pubkey(),
+                &authority.pubkey(),
+                offset as u32,
+                chunk.to_vec(),
+            );
+            futures_vec
+                .push(async move { self.send_loader_transaction(&[write_ix], [authority]).await });
+            offset += chunk.len();
+        }
+        stream::iter(futures_vec)
+            .buffer_unordered(500)
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+
+        buffer.pubkey()
+    }
+
+    /// Sends the transaction of the upgradeable BPF loader with the client [SendOptions]
+    /// without the compute budget instructions, see [Client::deploy].
+    #[throws]
+    async fn send_loader_transaction(
+        &self,
+        instructions: &[Instruction],
+        signers: impl IntoIterator<Item = &Keypair> + Send,
+    ) -> TransactionOutcome {
+        let options = SendOptions {
+            compute_unit_limit: None,
+            compute_unit_price: None,
+            heap_frame_size: None,
+            ..self.send_options.clone()
+        };
+        self.send_transaction_with_options(instructions, signers, &options)
+            .await?
+    }
+
+    /// Creates accounts.
+    #[throws]
+    pub async fn create_account(
+        &self,
+        keypair: &Keypair,
+        lamports: u64,
+        space: u64,
+        owner: &Pubkey,
+    ) -> TransactionOutcome {
+        self.send_transaction(
+            &[system_instruction::create_account(
+                &self.payer().pubkey(),
+                &keypair.pubkey(),
+                lamports,
+                space,
+                owner,
+            )],
+            [keypair],
+        )
+        .await?
+    }
+
+    /// Creates rent exempt account.
+    #[throws]
+    pub async fn create_account_rent_exempt(
+        &mut self,
+        keypair: &Keypair,
+        space: u64,
+        owner: &Pubkey,
+    ) -> TransactionOutcome {
+        self.send_transaction(
+            &[system_instruction::create_account(
+                &self.payer().pubkey(),
+                &keypair.pubkey(),
+                self.backend
+                    .get_minimum_balance_for_rent_exemption(space as usize)
+                    .await?,
+                space,
+                owner,
+            )],
+            [keypair],
+        )
+        .await?
+    }
+
+    /// Executes a transaction constructing a token mint of the token program,
+    /// either `spl_token::ID` or `spl_token_2022::ID`.
+    ///
+    /// Use [Client::create_token_2022_mint] to create a Token-2022 mint with extensions.
+    #[throws]
+    pub async fn create_token_mint(
+        &self,
+        token_program: Pubkey,
+        mint: &Keypair,
+        authority: Pubkey,
+        freeze_authority: Option<Pubkey>,
+        decimals: u8,
+    ) -> TransactionOutcome {
+        self.send_transaction(
+            &[
+                system_instruction::create_account(
+                    &self.payer().pubkey(),
+                    &mint.pubkey(),
+                    self.backend
+                        .get_minimum_balance_for_rent_exemption(spl_token::state::Mint::LEN)
+                        .await?,
+                    spl_token::state::Mint::LEN as u64,
+                    &token_program,
+                ),
+                token_instruction::initialize_mint(
+                    &token_program,
+                    &mint.pubkey(),
+                    &authority,
+                    freeze_authority.as_ref(),
+                    decimals,
+                )?,
+            ],
+            [mint],
+        )
+        .await?
+    }
+
+    /// Executes a transaction constructing a Token-2022 mint with the extensions.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// client
+    ///     .create_token_2022_mint(
+    ///         &mint,
+    ///         authority.pubkey(),
+    ///         None,
+    ///         6,
+    ///         &[MintExtension::TransferFee {
+    ///             authority: Some(authority.pubkey()),
+    ///             withdraw_withheld_authority: Some(authority.pubkey()),
+    ///             basis_points: 100,
+    ///             maximum_fee: 1_000,
+    ///         }],
+    ///     )
+    ///     .await?;
+    /// ```
+    #[throws]
+    pub async fn create_token_2022_mint(
+        &self,
+        mint: &Keypair,
+        authority: Pubkey,
+        freeze_authority: Option<Pubkey>,
+        decimals: u8,
+        extensions: &[MintExtension],
+    ) -> TransactionOutcome {
+        let extension_types = extensions
+            .iter()
+            .map(MintExtension::extension_type)
+            .collect::<Vec<_>>();
+        let space = ExtensionType::get_account_len::<spl_token_2022::state::Mint>(&extension_types);
+
+        let mut instructions = vec![system_instruction::create_account(
+            &self.payer().pubkey(),
+            &mint.pubkey(),
+            self.backend
+                .get_minimum_balance_for_rent_exemption(space)
+                .await?,
+            space as u64,
+            &spl_token_2022::ID,
+        )];
+        for extension in extensions {
+            instructions.push(extension.initialize_instruction(&mint.pubkey())?);
+        }
+        instructions.push(token_instruction::initialize_mint(
+            &spl_token_2022::ID,
+            &mint.pubkey(),
+            &authority,
+            freeze_authority.as_ref(),
+            decimals,
+        )?);
+        self.send_transaction(&instructions, [mint]).await?
+    }
+
+    /// Executes a transaction that mints tokens from a mint to an account belonging to that mint.
+    #[throws]
+    pub async fn mint_tokens(
+        &self,
+        token_program: Pubkey,
+        mint: Pubkey,
+        authority: &Keypair,
+        account: Pubkey,
+        amount: u64,
+    ) -> TransactionOutcome {
+        self.send_transaction(
+            &[token_instruction::mint_to(
+                &token_program,
+                &mint,
+                &account,
+                &authority.pubkey(),
+                &[],
+                amount,
+            )?],
+            [authority],
+        )
+        .await?
+    }
+
+    /// Executes a transaction constructing a token account of the specified mint. The account needs to be empty and belong to system for this to work.
+    /// Prefer to use [create_associated_token_account] if you don't need the provided account to contain the token account.
+    ///
+    /// The Token-2022 account is created with the space required by the extensions of the mint.
+    #[throws]
+    pub async fn create_token_account(
+        &self,
+        token_program: Pubkey,
+        account: &Keypair,
+        mint: &Pubkey,
+        owner: &Pubkey,
+    ) -> TransactionOutcome {
+        let space = if token_program == spl_token_2022::ID {
+            let mint_extension_types = self.get_mint_extension_types(*mint).await?;
+            ExtensionType::get_account_len::<spl_token_2022::state::Account>(
+                &ExtensionType::get_required_init_account_extensions(&mint_extension_types),
+            )
+        } else {
+            spl_token::state::Account::LEN
+        };
+        self.send_transaction(
+            &[
+                system_instruction::create_account(
+                    &self.payer().pubkey(),
+                    &account.pubkey(),
+                    self.backend
+                        .get_minimum_balance_for_rent_exemption(space)
+                        .await?,
+                    space as u64,
+                    &token_program,
+                ),
+                token_instruction::initialize_account(
+                    &token_program,
+                    &account.pubkey(),
+                    mint,
+                    owner,
+                )?,
+            ],
+            [account],
+        )
+        .await?
+    }
+
+    /// Executes a transaction constructing the associated token account of the specified mint belonging to the owner. This will fail if the account already exists.
+    #[throws]
+    pub async fn create_associated_token_account(
+        &self,
+        token_program: Pubkey,
+        owner: &Keypair,
+        mint: Pubkey,
+    ) -> Pubkey {
+        self.send_transaction(
+            &[create_associated_token_account(
+                &self.payer().pubkey(),
+                &owner.pubkey(),
+                &mint,
+                &token_program,
+            )],
+            &[],
+        )
+        .await?;
+        get_associated_token_address_with_program_id(&owner.pubkey(), &mint, &token_program)
+    }
+
+    /// Executes a transaction transferring tokens between the token accounts of the `mint`.
+    ///
+    /// The `authority` is the owner or the delegate of the `source` account.
+    /// The fee of a Token-2022 mint with the transfer fee is withheld in the `destination`.
+    #[throws]
+    #[allow(clippy::too_many_arguments)]
+    pub async fn transfer_tokens_checked(
+        &self,
+        token_program: Pubkey,
+        source: Pubkey,
+        mint: Pubkey,
+        destination: Pubkey,
+        authority: &Keypair,
+        amount: u64,
+        decimals: u8,
+    ) -> TransactionOutcome {
+        self.send_transaction(
+            &[token_instruction::transfer_checked(
+                &token_program,
+                &source,
+                &mint,
+                &destination,
+                &authority.pubkey(),
+                &[],
+                amount,
+                decimals,
+            )?],
+            [authority],
+        )
+        .await?
+    }
+
+    /// Executes a transaction approving the `delegate` to transfer or burn up to `amount` tokens
+    /// from the token account.
+    #[throws]
+    pub async fn approve_delegate(
+        &self,
+        token_program: Pubkey,
+        account: Pubkey,
+        delegate: Pubkey,
+        owner: &Keypair,
+        amount: u64,
+    ) -> TransactionOutcome {
+        self.send_transaction(
+            &[token_instruction::approve(
+                &token_program,
+                &account,
+                &delegate,
+                &owner.pubkey(),
+                &[],
+                amount,
+            )?],
+            [owner],
+        )
+        .await?
+    }
+
+    /// Executes a transaction revoking the delegate of the token account.
+    #[throws]
+    pub async fn revoke_delegate(
+        &self,
+        token_program: Pubkey,
+        account: Pubkey,
+        owner: &Keypair,
+    ) -> TransactionOutcome {
+        self.send_transaction(
+            &[token_instruction::revoke(
+                &token_program,
+                &account,
+                &owner.pubkey(),
+                &[],
+            )?],
+            [owner],
+        )
+        .await?
+    }
+
+    /// Executes a transaction burning tokens from the token account.
+    ///
+    /// The `authority` is the owner or the delegate of the account.
+    #[throws]
+    pub async fn burn_tokens(
+        &self,
+        token_program: Pubkey,
+        account: Pubkey,
+        mint: Pubkey,
+        authority: &Keypair,
+        amount: u64,
+    ) -> TransactionOutcome {
+        self.send_transaction(
+            &[token_instruction::burn(
+                &token_program,
+                &account,
+                &mint,
+                &authority.pubkey(),
+                &[],
+                amount,
+            )?],
+            [authority],
+        )
+        .await?
+    }
+
+    /// Executes a transaction freezing the token account with the freeze authority of the `mint`.
+    #[throws]
+    pub async fn freeze_token_account(
+        &self,
+        token_program: Pubkey,
+        account: Pubkey,
+        mint: Pubkey,
+        freeze_authority: &Keypair,
+    ) -> TransactionOutcome {
+        self.send_transaction(
+            &[token_instruction::freeze_account(
+                &token_program,
+                &account,
+                &mint,
+                &freeze_authority.pubkey(),
+                &[],
+            )?],
+            [freeze_authority],
+        )
+        .await?
+    }
+
+    /// Executes a transaction thawing the frozen token account with the freeze authority of the `mint`.
+    #[throws]
+    pub async fn thaw_token_account(
+        &self,
+        token_program: Pubkey,
+        account: Pubkey,
+        mint: Pubkey,
+        freeze_authority: &Keypair,
+    ) -> TransactionOutcome {
+        self.send_transaction(
+            &[token_instruction::thaw_account(
+                &token_program,
+                &account,
+                &mint,
+                &freeze_authority.pubkey(),
+                &[],
+            )?],
+            [freeze_authority],
+        )
+        .await?
+    }
+
+    /// Executes a transaction changing the authority of the mint or the token account.
+    ///
+    /// The authority is removed when `new_authority` is `None`.
+    /// The authority types of the Token-2022 extensions are supported by `spl_token_2022::ID` only.
+    #[throws]
+    pub async fn set_token_authority(
+        &self,
+        token_program: Pubkey,
+        mint_or_account: Pubkey,
+        authority_type: AuthorityType,
+        current_authority: &Keypair,
+        new_authority: Option<Pubkey>,
+    ) -> TransactionOutcome {
+        self.send_transaction(
+            &[token_instruction::set_authority(
+                &token_program,
+                &mint_or_account,
+                new_authority.as_ref(),
+                authority_type,
+                &current_authority.pubkey(),
+                &[],
+            )?],
+            [current_authority],
+        )
+        .await?
+    }
+
+    /// Executes a transaction closing the token account and transferring its lamports to the `destination`.
+    ///
+    /// Only the accounts without tokens (except wrapped SOL accounts) can be closed.
+    #[throws]
+    pub async fn close_token_account(
+        &self,
+        token_program: Pubkey,
+        account: Pubkey,
+        destination: Pubkey,
+        owner: &Keypair,
+    ) -> TransactionOutcome {
+        self.send_transaction(
+            &[token_instruction::close_account(
+                &token_program,
+                &account,
+                &destination,
+                &owner.pubkey(),
+                &[],
+            )?],
+            [owner],
+        )
+        .await?
+    }
+
+    /// Executes a transaction synchronizing the token amount of the wrapped SOL account with its lamports.
     #[throws]
-    pub async fn get_token_balance(&mut self, address: Pubkey) -> UiTokenAmount {
-        let rpc_client = self.anchor_client.program(System::id())?.async_rpc();
-        rpc_client.get_token_account_balance(&address).await?
+    pub async fn sync_native(&self, token_program: Pubkey, account: Pubkey) -> TransactionOutcome {
+        self.send_transaction(
+            &[token_instruction::sync_native(&token_program, &account)?],
+            [],
+        )
+        .await?
     }
 
-    /// Deploys a program based on it's name.
-    /// This function wraps boilerplate code required for the successful deployment of a program,
-    /// i.e. SOLs airdrop etc.
+    /// Executes a transaction wrapping `lamports` of the payer into the associated wrapped SOL
+    /// account of the `owner`. The account is created when it does not exist.
+    #[throws]
+    pub async fn create_wrapped_sol_account(
+        &self,
+        token_program: Pubkey,
+        owner: Pubkey,
+        lamports: u64,
+    ) -> Pubkey {
+        let native_mint = if token_program == spl_token_2022::ID {
+            spl_token_2022::native_mint::ID
+        } else {
+            spl_token::native_mint::ID
+        };
+        let account =
+            get_associated_token_address_with_program_id(&owner, &native_mint, &token_program);
+        self.send_transaction(
+            &[
+                create_associated_token_account_idempotent(
+                    &self.payer().pubkey(),
+                    &owner,
+                    &native_mint,
+                    &token_program,
+                ),
+                system_instruction::transfer(&self.payer().pubkey(), &account, lamports),
+                token_instruction::sync_native(&token_program, &account)?,
+            ],
+            [],
+        )
+        .await?;
+        account
+    }
+
+    /// Gets the unpacked token mint of either token program.
     ///
-    /// # Arguments
+    /// # Errors
     ///
-    /// * `program_keypair` - [Keypair] used for the program
-    /// * `program_name` - Name of the program to be deployed
+    /// It fails when the account does not exist or it is not a token mint.
+    #[throws]
+    pub async fn get_mint(&self, mint: Pubkey) -> spl_token::state::Mint {
+        let data = self.get_token_program_account_data(mint).await?;
+        StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+        // the extensions follow the base state of the legacy program
+        spl_token::state::Mint::unpack(&data[..spl_token::state::Mint::LEN])?
+    }
+
+    /// Gets the unpacked token account of either token program.
     ///
-    /// # Example:
+    /// # Errors
     ///
-    /// *Project structure*
+    /// It fails when the account does not exist or it is not a token account.
+    #[throws]
+    pub async fn get_token_account(&self, account: Pubkey) -> spl_token::state::Account {
+        let data = self.get_token_program_account_data(account).await?;
+        StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data)?;
+        // the extensions follow the base state of the legacy program
+        spl_token::state::Account::unpack(&data[..spl_token::state::Account::LEN])?
+    }
+
+    /// Gets the types of the extensions of the Token-2022 mint.
     ///
-    /// ```text
-    /// project/
-    /// - programs/
-    ///   - awesome_contract/
-    ///     - ...
-    ///     - Cargo.toml
-    ///   - turnstile/
-    ///     - ...
-    ///     - Cargo.toml
-    /// - ...
-    /// - Cargo.toml
-    /// ```
+    /// # Errors
     ///
-    /// *Code*
+    /// It fails when the account does not exist or it is not a token mint.
+    #[throws]
+    pub async fn get_mint_extension_types(&self, mint: Pubkey) -> Vec<ExtensionType> {
+        let data = self.get_token_program_account_data(mint).await?;
+        StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?.get_extension_types()?
+    }
+
+    /// Gets the unpacked extension of the Token-2022 mint.
+    ///
+    /// # Errors
+    ///
+    /// It fails when the account does not exist, it is not a token mint
+    /// or the mint does not have the extension.
+    ///
+    /// # Example
     ///
     /// ```rust,ignore
-    /// client.deploy_program(program_keypair(0), "awesome_contract");
-    /// client.deploy_program(program_keypair(1), "turnstile");
+    /// use spl_token_2022::extension::transfer_fee::TransferFeeConfig;
+    ///
+    /// let config = client.get_mint_extension::<TransferFeeConfig>(mint).await?;
+    /// assert_eq!(u16::from(config.newer_transfer_fee.transfer_fee_basis_points), 100);
     /// ```
     #[throws]
-    pub async fn deploy_by_name(&self, program_keypair: &Keypair, program_name: &str) {
-        debug!("reading program data");
-
-        let reader = Reader::new();
-
-        let mut program_data = reader
-            .program_data(program_name)
-            .await
-            .expect("reading program data failed");
-
-        debug!("airdropping the minimum balance required to deploy the program");
-
-        // TODO: This will fail on devnet where airdrops are limited to 1 SOL
-
-        self.airdrop(self.payer().pubkey(), 5_000_000_000)
-            .await
-            .expect("airdropping for deployment failed");
-
-        debug!("deploying program");
-
-        self.deploy(program_keypair.clone(), mem::take(&mut program_data))
-            .await?;
-
-        // this will slow down the process because if we call program instruction right after deploy,
-        // data are not yet completely deployed so error occures
-        let deploy_done = loop {
-            match self.anchor_client.program(program_keypair.pubkey()) {
-                Ok(_) => {
-                    debug!("program deployed succefully");
-                    sleep(Duration::from_millis(1000));
-                    break true;
-                }
-                Err(_) => {
-                    sleep(Duration::from_millis(500));
-                }
-            }
-        };
-
-        // this is not necessarry, but we want to avoid "throws" macro warning of unused code
-        if !deploy_done {
-            throw!(Error::ProgramError(
-                solana_sdk::program_error::ProgramError::Custom(0)
-            ));
-        }
+    pub async fn get_mint_extension<E: Extension + Pod>(&self, mint: Pubkey) -> E {
+        let data = self.get_token_program_account_data(mint).await?;
+        *StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?.get_extension::<E>()?
     }
 
-    /// Deploys the program.
+    /// Gets the unpacked extension of the Token-2022 token account,
+    /// e.g. `TransferFeeAmount` with the withheld fees.
+    ///
+    /// # Errors
+    ///
+    /// It fails when the account does not exist, it is not a token account
+    /// or the account does not have the extension.
     #[throws]
-    async fn deploy(&self, program_keypair: Keypair, program_data: Vec<u8>) {
-        const PROGRAM_DATA_CHUNK_SIZE: usize = 900;
-
-        let program_data_len = program_data.len();
-
-        let rpc_client = self
-            .anchor_client
-            .program(System::id())
-            .unwrap()
-            .async_rpc();
-
-        let system_program = self.anchor_client.program(System::id()).unwrap();
-
-        debug!("program_data_len: {}", program_data_len);
-
-        debug!("create program account");
-
-        let min_balance_for_rent_exemption = rpc_client
-            .get_minimum_balance_for_rent_exemption(program_data_len)
-            .await
-            .unwrap();
-
-        let create_account_ix: Instruction = system_instruction::create_account(
-            &self.payer.pubkey(),
-            &program_keypair.pubkey(),
-            min_balance_for_rent_exemption,
-            program_data_len as u64,
-            &bpf_loader::id(),
-        );
-        system_program
-            .request()
-            .instruction(create_account_ix)
-            .signer(&program_keypair)
-            .send()
-            .await
-            .unwrap();
-
-        debug!("write program data");
-
-        let mut offset = 0usize;
-        let mut futures_vec = Vec::new();
+    pub async fn get_token_account_extension<E: Extension + Pod>(&self, account: Pubkey) -> E {
+        let data = self.get_token_program_account_data(account).await?;
+        *StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data)?
+            .get_extension::<E>()?
+    }
 
-        for chunk in program_data.chunks(PROGRAM_DATA_CHUNK_SIZE) {
-            let loader_write_ix = loader_instruction::write(
-                &program_keypair.pubkey(),
-                &bpf_loader::id(),
-                offset as u32,
-                chunk.to_vec(),
-            );
-            futures_vec.push(async {
-                let system_program = self.anchor_client.program(System::id()).unwrap();
-                system_program
-                    .request()
-                    .instruction(loader_write_ix)
-                    .signer(&program_keypair)
-                    .send()
-                    .await
-                    .unwrap();
-            });
-            offset += chunk.len();
+    /// Gets the data of the account owned by either token program.
+    #[throws]
+    async fn get_token_program_account_data(&self, address: Pubkey) -> Vec<u8> {
+        let account = self
+            .get_account(address)
+            .await?
+            .ok_or(Error::AccountNotFound)?;
+        if account.owner != spl_token::ID && account.owner != spl_token_2022::ID {
+            throw!(Error::ProgramError(ProgramError::IncorrectProgramId));
         }
-        stream::iter(futures_vec)
-            .buffer_unordered(500)
-            .collect::<Vec<_>>()
-            .await;
-
-        debug!("finalize program");
-
-        let loader_finalize_ix =
-            loader_instruction::finalize(&program_keypair.pubkey(), &bpf_loader::id());
-        system_program
-            .request()
-            .instruction(loader_finalize_ix)
-            .signer(&program_keypair)
-            .send()
-            .await
-            .unwrap();
-
-        debug!("program deployed");
+        account.data
     }
 
-    /// Creates accounts.
+    /// Executes a transaction creating the vote account of the validator `node`.
+    ///
+    /// The account is funded with the rent exempt reserve by the payer.
     #[throws]
-    pub async fn create_account(
+    pub async fn create_vote_account(
         &self,
-        keypair: &Keypair,
-        lamports: u64,
-        space: u64,
-        owner: &Pubkey,
-    ) -> EncodedConfirmedTransactionWithStatusMeta {
+        vote: &Keypair,
+        node: &Keypair,
+        authorized_voter: Pubkey,
+        authorized_withdrawer: Pubkey,
+        commission: u8,
+    ) -> TransactionOutcome {
+        // the default space is the size of the legacy vote state
+        let config = CreateVoteAccountConfig {
+            space: VoteState::size_of() as u64,
+            ..CreateVoteAccountConfig::default()
+        };
         self.send_transaction(
-            &[system_instruction::create_account(
+            &vote_instruction::create_account_with_config(
                 &self.payer().pubkey(),
-                &keypair.pubkey(),
-                lamports,
-                space,
-                owner,
-            )],
-            [keypair],
+                &vote.pubkey(),
+                &VoteInit {
+                    node_pubkey: node.pubkey(),
+                    authorized_voter,
+                    authorized_withdrawer,
+                    commission,
+                },
+                self.backend
+                    .get_minimum_balance_for_rent_exemption(config.space as usize)
+                    .await?,
+                config,
+            ),
+            [vote, node],
         )
         .await?
     }
 
-    /// Creates rent exempt account.
+    /// Executes a transaction creating the uninitialized stake account with the `lamports`
+    /// paid by the payer; they have to cover the rent exempt reserve and the stake.
+    ///
+    /// Use [Client::initialize_stake_account] to initialize it.
     #[throws]
-    pub async fn create_account_rent_exempt(
-        &mut self,
-        keypair: &Keypair,
-        space: u64,
-        owner: &Pubkey,
-    ) -> EncodedConfirmedTransactionWithStatusMeta {
-        let rpc_client = self.anchor_client.program(System::id())?.async_rpc();
+    pub async fn create_stake_account(&self, stake: &Keypair, lamports: u64) -> TransactionOutcome {
         self.send_transaction(
             &[system_instruction::create_account(
                 &self.payer().pubkey(),
-                &keypair.pubkey(),
-                rpc_client
-                    .get_minimum_balance_for_rent_exemption(space as usize)
-                    .await?,
-                space,
-                owner,
+                &stake.pubkey(),
+                lamports,
+                StakeState::size_of() as u64,
+                &stake::program::ID,
             )],
-            [keypair],
+            [stake],
         )
         .await?
     }
 
-    /// Executes a transaction constructing a token mint.
+    /// Executes a transaction initializing the stake account with its staker and withdrawer
+    /// authorities and the lockup.
     #[throws]
-    pub async fn create_token_mint(
+    pub async fn initialize_stake_account(
         &self,
-        mint: &Keypair,
-        authority: Pubkey,
-        freeze_authority: Option<Pubkey>,
-        decimals: u8,
-    ) -> EncodedConfirmedTransactionWithStatusMeta {
-        let rpc_client = self.anchor_client.program(System::id())?.async_rpc();
+        stake: Pubkey,
+        authorized: Authorized,
+        lockup: Lockup,
+    ) -> TransactionOutcome {
         self.send_transaction(
-            &[
-                system_instruction::create_account(
-                    &self.payer().pubkey(),
-                    &mint.pubkey(),
-                    rpc_client
-                        .get_minimum_balance_for_rent_exemption(spl_token::state::Mint::LEN)
-                        .await?,
-                    spl_token::state::Mint::LEN as u64,
-                    &spl_token::ID,
-                ),
-                spl_token::instruction::initialize_mint(
-                    &spl_token::ID,
-                    &mint.pubkey(),
-                    &authority,
-                    freeze_authority.as_ref(),
-                    decimals,
-                )
-                .unwrap(),
-            ],
-            [mint],
+            &[stake_instruction::initialize(&stake, &authorized, &lockup)],
+            [],
         )
         .await?
     }
 
-    /// Executes a transaction that mints tokens from a mint to an account belonging to that mint.
+    /// Executes a transaction delegating the stake to the vote account.
+    ///
+    /// The stake is activated in the following epochs, see [Client::warp_to_slot].
     #[throws]
-    pub async fn mint_tokens(
+    pub async fn delegate_stake(
         &self,
-        mint: Pubkey,
-        authority: &Keypair,
-        account: Pubkey,
-        amount: u64,
-    ) -> EncodedConfirmedTransactionWithStatusMeta {
+        stake: Pubkey,
+        vote: Pubkey,
+        staker: &Keypair,
+    ) -> TransactionOutcome {
         self.send_transaction(
-            &[spl_token::instruction::mint_to(
-                &spl_token::ID,
-                &mint,
-                &account,
-                &authority.pubkey(),
-                &[],
-                amount,
-            )
-            .unwrap()],
-            [authority],
+            &[stake_instruction::delegate_stake(
+                &stake,
+                &staker.pubkey(),
+                &vote,
+            )],
+            [staker],
         )
         .await?
     }
 
-    /// Executes a transaction constructing a token account of the specified mint. The account needs to be empty and belong to system for this to work.
-    /// Prefer to use [create_associated_token_account] if you don't need the provided account to contain the token account.
+    /// Executes a transaction deactivating the delegated stake.
+    ///
+    /// The stake is deactivated at the end of the epoch.
     #[throws]
-    pub async fn create_token_account(
+    pub async fn deactivate_stake(&self, stake: Pubkey, staker: &Keypair) -> TransactionOutcome {
+        self.send_transaction(
+            &[stake_instruction::deactivate_stake(
+                &stake,
+                &staker.pubkey(),
+            )],
+            [staker],
+        )
+        .await?
+    }
+
+    /// Executes a transaction splitting the `lamports` from the stake account to the new `split_stake` account.
+    ///
+    /// The payer funds the rent exempt reserve of the new account.
+    #[throws]
+    pub async fn split_stake(
         &self,
-        account: &Keypair,
-        mint: &Pubkey,
-        owner: &Pubkey,
-    ) -> EncodedConfirmedTransactionWithStatusMeta {
-        let rpc_client = self.anchor_client.program(System::id())?.async_rpc();
+        stake: Pubkey,
+        staker: &Keypair,
+        lamports: u64,
+        split_stake: &Keypair,
+    ) -> TransactionOutcome {
+        let mut instructions = vec![system_instruction::transfer(
+            &self.payer().pubkey(),
+            &split_stake.pubkey(),
+            self.backend
+                .get_minimum_balance_for_rent_exemption(StakeState::size_of())
+                .await?,
+        )];
+        instructions.extend(stake_instruction::split(
+            &stake,
+            &staker.pubkey(),
+            lamports,
+            &split_stake.pubkey(),
+        ));
+        self.send_transaction(&instructions, [staker, split_stake])
+            .await?
+    }
+
+    /// Executes a transaction merging the `source` stake account into the `destination` one.
+    ///
+    /// The accounts have to have the same authorities and lockup and compatible activation states.
+    #[throws]
+    pub async fn merge_stake(
+        &self,
+        destination: Pubkey,
+        source: Pubkey,
+        staker: &Keypair,
+    ) -> TransactionOutcome {
         self.send_transaction(
-            &[
-                system_instruction::create_account(
-                    &self.payer().pubkey(),
-                    &account.pubkey(),
-                    rpc_client
-                        .get_minimum_balance_for_rent_exemption(spl_token::state::Account::LEN)
-                        .await?,
-                    spl_token::state::Account::LEN as u64,
-                    &spl_token::ID,
-                ),
-                spl_token::instruction::initialize_account(
-                    &spl_token::ID,
-                    &account.pubkey(),
-                    mint,
-                    owner,
-                )
-                .unwrap(),
-            ],
-            [account],
+            &stake_instruction::merge(&destination, &source, &staker.pubkey()),
+            [staker],
         )
         .await?
     }
 
-    /// Executes a transaction constructing the associated token account of the specified mint belonging to the owner. This will fail if the account already exists.
+    /// Executes a transaction withdrawing the unstaked `lamports` from the stake account to the `recipient`.
     #[throws]
-    pub async fn create_associated_token_account(&self, owner: &Keypair, mint: Pubkey) -> Pubkey {
+    pub async fn withdraw_stake(
+        &self,
+        stake: Pubkey,
+        withdrawer: &Keypair,
+        recipient: Pubkey,
+        lamports: u64,
+    ) -> TransactionOutcome {
         self.send_transaction(
-            #[allow(deprecated)]
-            &[create_associated_token_account(
-                &self.payer().pubkey(),
-                &owner.pubkey(),
-                &mint,
+            &[stake_instruction::withdraw(
+                &stake,
+                &withdrawer.pubkey(),
+                &recipient,
+                lamports,
+                None,
             )],
-            &[],
+            [withdrawer],
         )
-        .await?;
-        get_associated_token_address(&owner.pubkey(), &mint)
+        .await?
+    }
+
+    /// Gets the deserialized state of the stake account.
+    ///
+    /// _Note_: It returns `StakeState`, not `StakeStateV2` - the Solana 1.16 SDK this crate depends on
+    /// has no `StakeStateV2`. The 1.17 SDK adds `StakeStateV2` with the same account layout
+    /// (the stake flags take the former padding), so the return type changes with the SDK upgrade.
+    ///
+    /// # Errors
+    ///
+    /// It fails when the account does not exist or it is not a stake account.
+    #[throws]
+    pub async fn get_stake_state(&self, stake: Pubkey) -> StakeState {
+        let account = self
+            .get_account(stake)
+            .await?
+            .ok_or(Error::AccountNotFound)?;
+        if account.owner != stake::program::ID {
+            throw!(Error::ProgramError(ProgramError::IncorrectProgramId));
+        }
+        bincode::deserialize(&account.data).map_err(|error| {
+            Error::SolanaClientError(ClientErrorKind::Custom(error.to_string()).into())
+        })?
+    }
+
+    /// Gets the deserialized state of the vote account.
+    ///
+    /// # Errors
+    ///
+    /// It fails when the account does not exist or it is not a vote account.
+    #[throws]
+    pub async fn get_vote_state(&self, vote: Pubkey) -> VoteState {
+        let account = self
+            .get_account(vote)
+            .await?
+            .ok_or(Error::AccountNotFound)?;
+        if account.owner != vote::program::ID {
+            throw!(Error::ProgramError(ProgramError::IncorrectProgramId));
+        }
+        VoteState::deserialize(&account.data).map_err(loader_error)?
     }
 
     /// Executes a transaction creating and filling the given account with the given data.
@@ -664,12 +2494,11 @@ impl Client {
     pub async fn create_account_with_data(&self, account: &Keypair, data: Vec<u8>) {
         const DATA_CHUNK_SIZE: usize = 900;
 
-        let rpc_client = self.anchor_client.program(System::id())?.async_rpc();
         self.send_transaction(
             &[system_instruction::create_account(
                 &self.payer().pubkey(),
                 &account.pubkey(),
-                rpc_client
+                self.backend
                     .get_minimum_balance_for_rent_exemption(data.len())
                     .await?,
                 data.len() as u64,
@@ -718,3 +2547,14 @@ impl PrintableTransaction for EncodedConfirmedTransactionWithStatusMeta {
         }
     }
 }
+
+/// Converts the [InstructionError] returned by the `bpf_loader_upgradeable` instruction builders.
+fn loader_error(error: InstructionError) -> Error {
+    Error::SolanaClientError(ClientErrorKind::Custom(error.to_string()).into())
+}
+
+/// Returns the instruction name like `escrow::InitializeEscrow`
+/// for the Anchor instruction type `escrow::instruction::InitializeEscrow`.
+fn instruction_name<T>(_: &T) -> String {
+    std::any::type_name::<T>().replace("::instruction::", "::")
+}
@@ -1,14 +1,16 @@
 use crate::{
+    compute_units::CU_REPORT_ENV,
     idl::{self, Idl},
     program_client_generator,
     test_generator::TESTS_WORKSPACE,
-    Client,
+    Client, ComputeUnitBaseline, CU_BASELINE_FILE,
 };
 use cargo_metadata::{MetadataCommand, Package};
 use fehler::{throw, throws};
 use futures::future::try_join_all;
 use log::debug;
-use solana_sdk::signer::keypair::Keypair;
+use solana_account_decoder::{UiAccount, UiAccountEncoding};
+use solana_sdk::{account::Account, pubkey::Pubkey, signer::keypair::Keypair};
 use std::{
     borrow::Cow, io, iter, os::unix::process::CommandExt, path::Path, process::Stdio,
     string::FromUtf8Error,
@@ -22,6 +24,12 @@ use tokio::{
 
 pub static PROGRAM_CLIENT_DIRECTORY: &str = ".program_client";
 
+/// The file the tests record the consumed compute units to, relative to the root.
+const CU_REPORT_PATH: &str = "target/trdelnik/cu-report.jsonl";
+
+/// The directory with the accounts created in the genesis of the localnet, relative to the root.
+const GENESIS_ACCOUNTS_DIRECTORY: &str = "target/trdelnik/genesis-accounts";
+
 #[derive(Error, Debug)]
 pub enum Error {
     #[error("{0:?}")]
@@ -50,6 +58,8 @@ pub enum Error {
     NotInitialized,
     #[error("the crash file does not exist")]
     CrashFileNotFound,
+    #[error("compute units consumption regressed")]
+    ComputeUnitsRegression,
 }
 
 /// Localnet (the validator process) handle.
@@ -116,12 +126,7 @@ impl Commander {
     /// Builds programs (smart contracts).
     #[throws]
     pub async fn build_programs(&self) {
-        let success = Command::new("cargo")
-            .arg("build-bpf")
-            .arg("--")
-            // prevent prevent dependency loop:
-            // program tests -> program_client -> program
-            .args(["-Z", "avoid-dev-deps"])
+        let success = Self::build_programs_command()
             .spawn()?
             .wait()
             .await?
@@ -131,6 +136,18 @@ impl Commander {
         }
     }
 
+    /// Returns the `cargo build-bpf` command building the programs of the current directory.
+    pub(crate) fn build_programs_command() -> Command {
+        let mut command = Command::new("cargo");
+        command
+            .arg("build-bpf")
+            .arg("--")
+            // prevent prevent dependency loop:
+            // program tests -> program_client -> program
+            .args(["-Z", "avoid-dev-deps"]);
+        command
+    }
+
     /// Runs standard Rust tests.
     ///
     /// _Note_: The [--nocapture](https://doc.rust-lang.org/cargo/commands/cargo-test.html#display-options) argument is used
@@ -150,6 +167,64 @@ impl Commander {
         }
     }
 
+    /// Runs standard Rust tests and compares the compute units consumed by the instructions
+    /// sent with [Client::send_instruction] with the baseline `trdelnik-tests/cu-baseline.json`.
+    ///
+    /// The table of the baseline and current numbers is printed. The instructions consuming
+    /// more than `threshold_percent` % more compute units than in the baseline are regressions.
+    ///
+    /// The baseline is (re)written instead of the comparison when `update_baseline` is `true`
+    /// or when it does not exist yet.
+    ///
+    /// # Errors
+    ///
+    /// It fails when:
+    /// - the tests failed.
+    /// - the baseline or the recorded compute units cannot be read.
+    /// - an instruction regressed.
+    #[throws]
+    pub async fn run_tests_with_cu_check(&self, threshold_percent: f64, update_baseline: bool) {
+        let root = fs::canonicalize(self.root.as_ref()).await?;
+        let report_path = root.join(CU_REPORT_PATH);
+        if let Some(report_directory) = report_path.parent() {
+            fs::create_dir_all(report_directory).await?;
+        }
+        if report_path.try_exists()? {
+            fs::remove_file(&report_path).await?;
+        }
+
+        let success = Command::new("cargo")
+            .arg("test")
+            .env(CU_REPORT_ENV, &report_path)
+            .arg("--")
+            .arg("--nocapture")
+            .spawn()?
+            .wait()
+            .await?
+            .success();
+        if !success {
+            throw!(Error::TestingFailed);
+        }
+
+        let current = ComputeUnitBaseline::from_report(&report_path)?;
+        let baseline_path = root.join(TESTS_WORKSPACE).join(CU_BASELINE_FILE);
+        if update_baseline || !baseline_path.try_exists()? {
+            current.save(&baseline_path)?;
+            println!(
+                "compute units baseline saved to {}",
+                baseline_path.display()
+            );
+            return;
+        }
+
+        let report =
+            ComputeUnitBaseline::load(&baseline_path)?.compare(&current, threshold_percent);
+        println!("{report}");
+        if !report.is_ok() {
+            throw!(Error::ComputeUnitsRegression);
+        }
+    }
+
     /// Runs fuzzer on the given target.
     #[throws]
     pub async fn run_fuzzer(&self, target: String) {
@@ -359,12 +434,53 @@ impl Commander {
     /// Starts the localnet (Solana validator).
     #[throws]
     pub async fn start_localnet(&self) -> LocalnetHandle {
-        let mut process = Command::new("solana-test-validator")
+        self.start_localnet_with_accounts(&[]).await?
+    }
+
+    /// Starts the localnet (Solana validator) with the `accounts` created in the genesis,
+    /// e.g. the fixtures or the mocks of the accounts of foreign programs.
+    ///
+    /// The accounts are written to the `target/trdelnik/genesis-accounts` directory
+    /// and loaded by the validator with the `--account` option.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let escrow = Client::anchor_account(escrow::ID, &escrow::Escrow { /* ... */ })?;
+    /// let localnet = Commander::new()
+    ///     .start_localnet_with_accounts(&[(escrow_address, escrow)])
+    ///     .await?;
+    /// ```
+    #[throws]
+    pub async fn start_localnet_with_accounts(
+        &self,
+        accounts: &[(Pubkey, Account)],
+    ) -> LocalnetHandle {
+        let mut command = Command::new("solana-test-validator");
+        command
             .arg("-C")
             .arg([&self.root, "config.yml"].concat())
             .arg("-r")
-            .arg("-q")
-            .spawn()?;
+            .arg("-q");
+
+        let accounts_directory = Path::new(self.root.as_ref()).join(GENESIS_ACCOUNTS_DIRECTORY);
+        if accounts_directory.try_exists()? {
+            fs::remove_dir_all(&accounts_directory).await?;
+        }
+        if !accounts.is_empty() {
+            fs::create_dir_all(&accounts_directory).await?;
+        }
+        for (address, account) in accounts {
+            // the format of `solana account --output json`
+            let account_json = serde_json::json!({
+                "pubkey": address.to_string(),
+                "account": UiAccount::encode(address, account, UiAccountEncoding::Base64, None, None),
+            });
+            let path = accounts_directory.join(format!("{address}.json"));
+            fs::write(&path, account_json.to_string()).await?;
+            command.arg("--account").arg(address.to_string()).arg(path);
+        }
+        let mut process = command.spawn()?;
 
         if !Client::new(Keypair::new()).is_localnet_running(true).await {
             // The validator might not be running, but the process might be still alive (very slow start, some bug, ...),
@@ -0,0 +1,265 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    env, fmt,
+    fs::{self, OpenOptions},
+    io::{self, BufRead, BufReader, Write},
+    path::Path,
+    thread,
+};
+
+/// The environment variable with the path of the file the consumed compute units are recorded to.
+///
+/// It is set by [Commander::run_tests_with_cu_check](crate::Commander::run_tests_with_cu_check).
+pub(crate) const CU_REPORT_ENV: &str = "TRDELNIK_CU_REPORT";
+
+/// The name of the baseline file in the `trdelnik-tests` directory.
+pub const CU_BASELINE_FILE: &str = "cu-baseline.json";
+
+/// A line of the compute units report written by the tests.
+#[derive(Serialize, Deserialize)]
+struct ReportLine {
+    test: String,
+    instruction: String,
+    units: u64,
+}
+
+/// Records the compute units consumed by the instruction in the current test,
+/// if the tests are run with [CU_REPORT_ENV].
+///
+/// The test is identified by the name of the current thread, i.e. the test name
+/// when the test runs on the thread spawned for it by the test harness.
+pub(crate) fn record_compute_units(instruction: &str, units: u64) {
+    let path = match env::var_os(CU_REPORT_ENV) {
+        Some(path) => path,
+        None => return,
+    };
+    let line = ReportLine {
+        test: thread::current().name().unwrap_or("unknown").to_string(),
+        instruction: instruction.to_string(),
+        units,
+    };
+    let result = serde_json::to_string(&line)
+        .map_err(io::Error::from)
+        .and_then(|line| {
+            // the whole line is written at once, so the parallel tests do not interleave it
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)?
+                .write_all(format!("{line}\n").as_bytes())
+        });
+    if let Err(error) = result {
+        eprintln!("cannot record the compute units of `{instruction}`: {error}");
+    }
+}
+
+/// The compute units consumed by the instructions, grouped by tests.
+///
+/// When an instruction is sent more than once in a test, the highest consumption is kept.
+/// It is stored as the JSON file `trdelnik-tests/cu-baseline.json`:
+///
+/// ```json
+/// {
+///   "test_happy_path": {
+///     "escrow::InitializeEscrow": 12345
+///   }
+/// }
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ComputeUnitBaseline(BTreeMap<String, BTreeMap<String, u64>>);
+
+impl ComputeUnitBaseline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads the baseline from the JSON file.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// Saves the baseline to the JSON file.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)? + "\n")
+    }
+
+    /// Reads the compute units recorded by the tests.
+    pub(crate) fn from_report(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut baseline = Self::new();
+        let file = match fs::File::open(path) {
+            Ok(file) => file,
+            // no instruction has been sent
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(baseline),
+            Err(error) => return Err(error),
+        };
+        for line in BufReader::new(file).lines() {
+            let line: ReportLine = serde_json::from_str(&line?)?;
+            baseline.insert(line.test, line.instruction, line.units);
+        }
+        Ok(baseline)
+    }
+
+    /// Inserts the compute units consumed by the instruction in the test,
+    /// unless a higher consumption has been inserted already.
+    pub fn insert(&mut self, test: impl Into<String>, instruction: impl Into<String>, units: u64) {
+        let recorded = self
+            .0
+            .entry(test.into())
+            .or_default()
+            .entry(instruction.into())
+            .or_default();
+        *recorded = (*recorded).max(units);
+    }
+
+    /// Gets the compute units consumed by the instruction in the test.
+    pub fn get(&self, test: &str, instruction: &str) -> Option<u64> {
+        self.0.get(test)?.get(instruction).copied()
+    }
+
+    /// Compares the `current` consumption with this baseline.
+    ///
+    /// The instructions consuming more than `threshold_percent` % more compute units
+    /// than in the baseline are reported as regressions.
+    pub fn compare(&self, current: &Self, threshold_percent: f64) -> ComputeUnitReport {
+        let keys = self
+            .keys()
+            .chain(current.keys())
+            .collect::<BTreeSet<(&str, &str)>>();
+        ComputeUnitReport {
+            changes: keys
+                .into_iter()
+                .map(|(test, instruction)| ComputeUnitChange {
+                    test: test.to_string(),
+                    instruction: instruction.to_string(),
+                    baseline: self.get(test, instruction),
+                    current: current.get(test, instruction),
+                })
+                .collect(),
+            threshold_percent,
+        }
+    }
+
+    fn keys(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0.iter().flat_map(|(test, instructions)| {
+            instructions
+                .keys()
+                .map(move |instruction| (test.as_str(), instruction.as_str()))
+        })
+    }
+}
+
+/// The compute units consumed by an instruction in a test before and after the changes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComputeUnitChange {
+    pub test: String,
+    pub instruction: String,
+    /// The baseline consumption; `None` for new instructions.
+    pub baseline: Option<u64>,
+    /// The current consumption; `None` for instructions not sent anymore.
+    pub current: Option<u64>,
+}
+
+impl ComputeUnitChange {
+    /// The relative change of the consumption in percent.
+    pub fn change_percent(&self) -> Option<f64> {
+        match (self.baseline?, self.current?) {
+            (0, 0) => Some(0.0),
+            (0, _) => Some(f64::INFINITY),
+            (baseline, current) => {
+                Some((current as f64 - baseline as f64) / baseline as f64 * 100.0)
+            }
+        }
+    }
+}
+
+/// The comparison of the compute units consumption with the baseline,
+/// see [ComputeUnitBaseline::compare].
+///
+/// It is printed as a table of the before/after numbers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComputeUnitReport {
+    pub changes: Vec<ComputeUnitChange>,
+    pub threshold_percent: f64,
+}
+
+impl ComputeUnitReport {
+    /// Iterates over the instructions consuming more compute units than the threshold allows.
+    pub fn regressions(&self) -> impl Iterator<Item = &ComputeUnitChange> {
+        self.changes.iter().filter(|change| {
+            change
+                .change_percent()
+                .map_or(false, |percent| percent > self.threshold_percent)
+        })
+    }
+
+    /// Returns `true` when there are no regressions.
+    pub fn is_ok(&self) -> bool {
+        self.regressions().next().is_none()
+    }
+}
+
+impl fmt::Display for ComputeUnitReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let units = |units: Option<u64>| units.map_or("-".to_string(), |units| units.to_string());
+        let rows = self
+            .changes
+            .iter()
+            .map(|change| {
+                let (change_text, status) = match change.change_percent() {
+                    Some(percent) if percent > self.threshold_percent => {
+                        (format!("{percent:+.2}%"), "REGRESSION")
+                    }
+                    Some(percent) => (format!("{percent:+.2}%"), "ok"),
+                    None if change.baseline.is_none() => ("-".to_string(), "new"),
+                    None => ("-".to_string(), "removed"),
+                };
+                [
+                    change.test.clone(),
+                    change.instruction.clone(),
+                    units(change.baseline),
+                    units(change.current),
+                    change_text,
+                    status.to_string(),
+                ]
+            })
+            .collect::<Vec<_>>();
+
+        let header = [
+            "test",
+            "instruction",
+            "baseline",
+            "current",
+            "change",
+            "status",
+        ]
+        .map(String::from);
+        let mut widths = header.clone().map(|column| column.len());
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.len());
+            }
+        }
+        for row in std::iter::once(&header).chain(&rows) {
+            let line = row
+                .iter()
+                .zip(widths)
+                .enumerate()
+                .map(|(column, (cell, width))| match column {
+                    // the numbers are aligned to the right
+                    2..=4 => format!("{cell:>width$}"),
+                    _ => format!("{cell:<width$}"),
+                })
+                .collect::<Vec<_>>()
+                .join("  ");
+            writeln!(f, "{}", line.trim_end())?;
+        }
+        write!(
+            f,
+            "{} regression(s) over the {}% threshold",
+            self.regressions().count(),
+            self.threshold_percent
+        )
+    }
+}
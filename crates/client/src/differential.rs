@@ -0,0 +1,372 @@
+use crate::{
+    error_reporter::{transaction_error, transaction_logs},
+    Client, TempClone,
+};
+use anchor_client::{
+    solana_sdk::{
+        account::Account, bpf_loader, bpf_loader_deprecated, bpf_loader_upgradeable,
+        instruction::Instruction, pubkey::Pubkey, rent::Rent, signer::keypair::Keypair,
+        transaction::TransactionError,
+    },
+    ClientError as Error,
+};
+use fehler::{throw, throws};
+use solana_program_test::ProgramTest;
+use std::{collections::BTreeSet, fmt};
+
+/// The number of bytes printed around the first differing byte of the account data.
+const DATA_DIFF_CONTEXT: usize = 16;
+
+/// Runs the same instructions against two builds of a program and compares the results.
+///
+/// Each build is processed in-process by its own [ProgramTest] [Client]. After each step the transaction
+/// results, the logs and all accounts referenced by the instructions so far are compared.
+/// The first divergence is recorded, see [DifferentialTest::divergence].
+///
+/// The program accounts themselves (executable accounts and accounts owned by the BPF loaders)
+/// are not compared as they differ by definition.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let mut baseline = ProgramTest::default();
+/// add_program_binary(&mut baseline, turnstile::ID, Reader::new().program_data_from_path("turnstile-1.0.so").await?);
+/// let candidate = ProgramTest::new("turnstile", turnstile::ID, processor!(turnstile::entry));
+///
+/// let mut test = DifferentialTest::new(system_keypair(0), baseline, candidate).await;
+/// test.step("initialize", &[turnstile_instruction::initialize_ix(/* ... */)], &[&state]).await?;
+/// test.step("coin", &[turnstile_instruction::coin_ix(/* ... */)], &[]).await?;
+/// test.assert_no_divergence();
+/// ```
+pub struct DifferentialTest {
+    baseline: Client,
+    candidate: Client,
+    compared_accounts: BTreeSet<Pubkey>,
+    compare_compute_units: bool,
+    steps: usize,
+    divergence: Option<Divergence>,
+}
+
+impl DifferentialTest {
+    /// Starts both program tests with the same `payer`.
+    pub async fn new(payer: Keypair, baseline: ProgramTest, candidate: ProgramTest) -> Self {
+        Self {
+            baseline: Client::with_program_test(payer.clone(), baseline).await,
+            candidate: Client::with_program_test(payer, candidate).await,
+            compared_accounts: BTreeSet::new(),
+            compare_compute_units: false,
+            steps: 0,
+            divergence: None,
+        }
+    }
+
+    /// Compares also the `Program ... consumed ... compute units` log lines.
+    ///
+    /// They are ignored by default because the consumed compute units change with almost any code change.
+    pub fn compare_compute_units(mut self, compare: bool) -> Self {
+        self.compare_compute_units = compare;
+        self
+    }
+
+    /// Gets the client processing transactions with the baseline build.
+    pub fn baseline(&self) -> &Client {
+        &self.baseline
+    }
+
+    /// Gets the client processing transactions with the candidate build.
+    pub fn candidate(&self) -> &Client {
+        &self.candidate
+    }
+
+    /// Adds the accounts compared after each step in addition to the accounts of the instructions.
+    pub fn compare_accounts(&mut self, accounts: impl IntoIterator<Item = Pubkey>) {
+        self.compared_accounts.extend(accounts);
+    }
+
+    /// Sends the instructions to both builds and compares the results.
+    ///
+    /// Failed transactions are compared too. The steps after the first divergence are still sent
+    /// but they are not compared anymore.
+    ///
+    /// # Errors
+    ///
+    /// It fails when a transaction could not be processed for a reason other than its failure,
+    /// e.g. when the backend is not reachable.
+    #[throws]
+    pub async fn step(&mut self, name: &str, instructions: &[Instruction], signers: &[&Keypair]) {
+        let index = self.steps;
+        self.steps += 1;
+
+        let baseline = StepResult::new(&self.baseline, instructions, signers).await?;
+        let candidate = StepResult::new(&self.candidate, instructions, signers).await?;
+
+        self.compared_accounts.extend(
+            instructions
+                .iter()
+                .flat_map(|instruction| instruction.accounts.iter().map(|meta| meta.pubkey)),
+        );
+        if self.divergence.is_some() {
+            return;
+        }
+
+        let kind = if baseline.error != candidate.error {
+            Some(DivergenceKind::Result {
+                baseline: baseline.error,
+                candidate: candidate.error,
+            })
+        } else if self.filter_logs(&baseline.logs) != self.filter_logs(&candidate.logs) {
+            Some(DivergenceKind::Logs {
+                baseline: self.filter_logs(&baseline.logs),
+                candidate: self.filter_logs(&candidate.logs),
+            })
+        } else {
+            self.compare_account_states().await?
+        };
+
+        self.divergence = kind.map(|kind| Divergence {
+            step: name.to_string(),
+            index,
+            kind,
+        });
+    }
+
+    /// Returns the first divergence of the builds.
+    pub fn divergence(&self) -> Option<&Divergence> {
+        self.divergence.as_ref()
+    }
+
+    /// Panics with the readable diff of the first divergence, if any.
+    pub fn assert_no_divergence(&self) {
+        if let Some(divergence) = &self.divergence {
+            panic!("{divergence}");
+        }
+    }
+
+    fn filter_logs(&self, logs: &[String]) -> Vec<String> {
+        logs.iter()
+            .filter(|log| self.compare_compute_units || !is_compute_units_log(log))
+            .cloned()
+            .collect()
+    }
+
+    #[throws]
+    async fn compare_account_states(&self) -> Option<DivergenceKind> {
+        for address in &self.compared_accounts {
+            let baseline = self.baseline.get_account(*address).await?;
+            let candidate = self.candidate.get_account(*address).await?;
+            if [&baseline, &candidate]
+                .into_iter()
+                .flatten()
+                .any(is_program_account)
+            {
+                continue;
+            }
+            if baseline != candidate {
+                return Some(DivergenceKind::Account {
+                    address: *address,
+                    baseline,
+                    candidate,
+                });
+            }
+        }
+        None
+    }
+}
+
+/// Adds the program binary (`.so`) to the program test, e.g. a binary of a previous release
+/// loaded with [Reader::program_data_from_path](crate::Reader::program_data_from_path).
+pub fn add_program_binary(
+    program_test: &mut ProgramTest,
+    program_id: Pubkey,
+    program_data: Vec<u8>,
+) {
+    program_test.add_account(
+        program_id,
+        Account {
+            lamports: Rent::default().minimum_balance(program_data.len()).max(1),
+            data: program_data,
+            owner: bpf_loader::id(),
+            executable: true,
+            rent_epoch: 0,
+        },
+    );
+}
+
+/// The first difference between the builds found by [DifferentialTest].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Divergence {
+    /// The name of the step.
+    pub step: String,
+    /// The index of the step, starting from `0`.
+    pub index: usize,
+    pub kind: DivergenceKind,
+}
+
+/// What differs between the builds.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DivergenceKind {
+    /// One build failed or the builds failed with different errors.
+    Result {
+        baseline: Option<TransactionError>,
+        candidate: Option<TransactionError>,
+    },
+    /// The transaction logs differ.
+    Logs {
+        baseline: Vec<String>,
+        candidate: Vec<String>,
+    },
+    /// The account lamports, owner or data differ, or the account exists only in one build.
+    Account {
+        address: Pubkey,
+        baseline: Option<Account>,
+        candidate: Option<Account>,
+    },
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "the builds diverged in the step #{} `{}`",
+            self.index, self.step
+        )?;
+        match &self.kind {
+            DivergenceKind::Result {
+                baseline,
+                candidate,
+            } => {
+                let result = |error: &Option<TransactionError>| match error {
+                    Some(error) => format!("Err({error})"),
+                    None => "Ok".to_string(),
+                };
+                writeln!(f, "- baseline result:  {}", result(baseline))?;
+                writeln!(f, "+ candidate result: {}", result(candidate))?;
+            }
+            DivergenceKind::Logs {
+                baseline,
+                candidate,
+            } => {
+                let first_difference = baseline
+                    .iter()
+                    .zip(candidate)
+                    .position(|(baseline, candidate)| baseline != candidate)
+                    .unwrap_or_else(|| baseline.len().min(candidate.len()));
+                writeln!(f, "logs differ from the line {first_difference}:")?;
+                for log in &baseline[..first_difference] {
+                    writeln!(f, "  {log}")?;
+                }
+                for log in &baseline[first_difference..] {
+                    writeln!(f, "- {log}")?;
+                }
+                for log in &candidate[first_difference..] {
+                    writeln!(f, "+ {log}")?;
+                }
+            }
+            DivergenceKind::Account {
+                address,
+                baseline,
+                candidate,
+            } => {
+                writeln!(f, "account {address} differs:")?;
+                match (baseline, candidate) {
+                    (Some(baseline), Some(candidate)) => {
+                        write_account_diff(f, baseline, candidate)?
+                    }
+                    (baseline, candidate) => {
+                        let exists = |account: &Option<Account>| match account {
+                            Some(_) => "exists",
+                            None => "does not exist",
+                        };
+                        writeln!(f, "- baseline:  {}", exists(baseline))?;
+                        writeln!(f, "+ candidate: {}", exists(candidate))?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn write_account_diff(
+    f: &mut fmt::Formatter<'_>,
+    baseline: &Account,
+    candidate: &Account,
+) -> fmt::Result {
+    if baseline.lamports != candidate.lamports {
+        writeln!(f, "- lamports: {}", baseline.lamports)?;
+        writeln!(f, "+ lamports: {}", candidate.lamports)?;
+    }
+    if baseline.owner != candidate.owner {
+        writeln!(f, "- owner: {}", baseline.owner)?;
+        writeln!(f, "+ owner: {}", candidate.owner)?;
+    }
+    if baseline.data.len() != candidate.data.len() {
+        writeln!(f, "- data length: {}", baseline.data.len())?;
+        writeln!(f, "+ data length: {}", candidate.data.len())?;
+    }
+    let first_difference = baseline
+        .data
+        .iter()
+        .zip(&candidate.data)
+        .position(|(baseline, candidate)| baseline != candidate);
+    if let Some(offset) = first_difference {
+        let start = offset.saturating_sub(DATA_DIFF_CONTEXT / 2);
+        let window = |data: &[u8]| {
+            data[start..data.len().min(start + DATA_DIFF_CONTEXT)]
+                .iter()
+                .map(|byte| format!("{byte:02x}"))
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        writeln!(
+            f,
+            "data differs from the byte {offset} (showing from {start}):"
+        )?;
+        writeln!(f, "- {}", window(&baseline.data))?;
+        writeln!(f, "+ {}", window(&candidate.data))?;
+    }
+    Ok(())
+}
+
+/// The result of a step processed by one build.
+struct StepResult {
+    error: Option<TransactionError>,
+    logs: Vec<String>,
+}
+
+impl StepResult {
+    #[throws]
+    async fn new(client: &Client, instructions: &[Instruction], signers: &[&Keypair]) -> Self {
+        match client
+            .send_transaction(instructions, signers.iter().copied())
+            .await
+        {
+            Ok(outcome) => Self {
+                error: None,
+                logs: outcome.logs,
+            },
+            Err(error) => match transaction_error(&error) {
+                Some(transaction_error) => Self {
+                    logs: transaction_logs(&error).unwrap_or_default().to_vec(),
+                    error: Some(transaction_error),
+                },
+                None => throw!(error),
+            },
+        }
+    }
+}
+
+fn is_program_account(account: &Account) -> bool {
+    account.executable
+        || [
+            bpf_loader::id(),
+            bpf_loader_deprecated::id(),
+            bpf_loader_upgradeable::id(),
+        ]
+        .contains(&account.owner)
+}
+
+/// Matches `Program <program_id> consumed <units> of <limit> compute units`.
+fn is_compute_units_log(log: &str) -> bool {
+    log.starts_with("Program ") && log.contains(" consumed ") && log.ends_with(" compute units")
+}
@@ -1,9 +1,14 @@
+use anchor_client::anchor_lang::error::{ErrorCode, ERROR_CODE_OFFSET};
 use anchor_client::solana_client::client_error::ClientErrorKind;
 use anchor_client::solana_client::rpc_request::RpcError::RpcResponseError;
 use anchor_client::solana_client::rpc_request::RpcResponseErrorData;
 use anchor_client::solana_client::rpc_response::RpcSimulateTransactionResult;
+use anchor_client::solana_sdk::{
+    instruction::InstructionError, pubkey::Pubkey, transaction::TransactionError,
+};
 use anchor_client::ClientError;
 use anyhow::Error;
+use std::str::FromStr;
 
 trait ErrorReporter<T> {
     fn report(error: &T);
@@ -29,6 +34,9 @@ impl ErrorReporter<ClientError> for ClientError {
                 println!("RpcResponseError [{code}]: {message}\n{formatted_data}");
             }
         }
+        if let Some(custom_error) = custom_program_error(error) {
+            println!("{custom_error}");
+        }
     }
 }
 
@@ -37,3 +45,266 @@ pub fn report_error(error: &Error) {
         ClientError::report(err);
     };
 }
+
+/// Errors the [ClientError] returned by [Client](crate::Client) can be extracted from.
+pub trait ClientErrorSource {
+    fn client_error(&self) -> Option<&ClientError>;
+}
+
+impl ClientErrorSource for ClientError {
+    fn client_error(&self) -> Option<&ClientError> {
+        Some(self)
+    }
+}
+
+impl ClientErrorSource for Error {
+    fn client_error(&self) -> Option<&ClientError> {
+        self.downcast_ref::<ClientError>()
+    }
+}
+
+/// The custom error (`InstructionError::Custom`) an instruction of a transaction failed with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomProgramError {
+    /// The index of the failed instruction in the transaction.
+    pub instruction_index: u8,
+    /// The program which raised the error, if it could be found in the transaction logs.
+    ///
+    /// It is the innermost failed program, i.e. the invoked program when the error is raised in a CPI,
+    /// not the program of the failed instruction.
+    pub program_id: Option<Pubkey>,
+    /// The custom error code.
+    pub code: u32,
+    /// The name of the Anchor error, e.g. `ConstraintMut` or `Unauthorized`.
+    pub name: Option<String>,
+    /// The message of the Anchor error.
+    pub message: Option<String>,
+}
+
+impl std::fmt::Display for CustomProgramError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Instruction #{}", self.instruction_index)?;
+        if let Some(program_id) = &self.program_id {
+            write!(f, " of the program {program_id}")?;
+        }
+        write!(f, " failed with the custom error {}", self.code)?;
+        if let Some(name) = &self.name {
+            write!(f, " `{name}`")?;
+        }
+        if let Some(message) = &self.message {
+            write!(f, ": {message}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the [TransactionError] the failed transaction was rejected with.
+pub fn transaction_error(error: &impl ClientErrorSource) -> Option<TransactionError> {
+    match error.client_error()? {
+        ClientError::SolanaClientError(err) => err.get_transaction_error(),
+        _ => None,
+    }
+}
+
+/// Returns the logs of the failed transaction simulation.
+pub fn transaction_logs(error: &impl ClientErrorSource) -> Option<&[String]> {
+    match error.client_error()? {
+        ClientError::SolanaClientError(err) => match err.kind() {
+            ClientErrorKind::RpcError(RpcResponseError {
+                data:
+                    RpcResponseErrorData::SendTransactionPreflightFailure(
+                        RpcSimulateTransactionResult {
+                            logs: Some(logs), ..
+                        },
+                    ),
+                ..
+            }) => Some(logs),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Finds out which instruction and program failed with a custom error and resolves
+/// the error code to the Anchor framework error or to the program's `#[error_code]` variant.
+///
+/// The name and the message of program errors are read from the `AnchorError` log.
+pub fn custom_program_error(error: &impl ClientErrorSource) -> Option<CustomProgramError> {
+    let (instruction_index, code) = match transaction_error(error)? {
+        TransactionError::InstructionError(index, InstructionError::Custom(code)) => (index, code),
+        _ => None?,
+    };
+    let logs = transaction_logs(error).unwrap_or_default();
+
+    // the invoked program fails before its callers
+    let program_id = logs.iter().find_map(|log| {
+        let program_id = log.strip_prefix("Program ")?.split_once(" failed: ")?.0;
+        Pubkey::from_str(program_id).ok()
+    });
+
+    let (name, message) = logs
+        .iter()
+        .rev()
+        .find_map(|log| parse_anchor_error_log(log).filter(|(_, number, _)| *number == code))
+        .map(|(name, _, message)| (Some(name), Some(message)))
+        .or_else(|| {
+            let error_code = anchor_error_code(code)?;
+            Some((Some(error_code.name()), Some(error_code.to_string())))
+        })
+        .unwrap_or_default();
+
+    Some(CustomProgramError {
+        instruction_index,
+        program_id,
+        code,
+        name,
+        message,
+    })
+}
+
+/// Parses `(name, number, message)` from the log line:
+/// `Program log: AnchorError ... Error Code: <name>. Error Number: <number>. Error Message: <message>.`
+fn parse_anchor_error_log(log: &str) -> Option<(String, u32, String)> {
+    let log = log.strip_prefix("Program log: AnchorError ")?;
+    let (_, rest) = log.split_once("Error Code: ")?;
+    let (name, rest) = rest.split_once(". Error Number: ")?;
+    let (number, message) = rest.split_once(". Error Message: ")?;
+    let message = message.strip_suffix('.').unwrap_or(message);
+    Some((name.to_string(), number.parse().ok()?, message.to_string()))
+}
+
+/// The Anchor framework errors, matched by their codes.
+const ANCHOR_ERROR_CODES: &[ErrorCode] = {
+    use ErrorCode::*;
+    &[
+        InstructionMissing,
+        InstructionFallbackNotFound,
+        InstructionDidNotDeserialize,
+        InstructionDidNotSerialize,
+        IdlInstructionStub,
+        IdlInstructionInvalidProgram,
+        IdlAccountNotEmpty,
+        EventInstructionStub,
+        ConstraintMut,
+        ConstraintHasOne,
+        ConstraintSigner,
+        ConstraintRaw,
+        ConstraintOwner,
+        ConstraintRentExempt,
+        ConstraintSeeds,
+        ConstraintExecutable,
+        ConstraintState,
+        ConstraintAssociated,
+        ConstraintAssociatedInit,
+        ConstraintClose,
+        ConstraintAddress,
+        ConstraintZero,
+        ConstraintTokenMint,
+        ConstraintTokenOwner,
+        ConstraintMintMintAuthority,
+        ConstraintMintFreezeAuthority,
+        ConstraintMintDecimals,
+        ConstraintSpace,
+        ConstraintAccountIsNone,
+        ConstraintTokenTokenProgram,
+        ConstraintMintTokenProgram,
+        ConstraintAssociatedTokenTokenProgram,
+        RequireViolated,
+        RequireEqViolated,
+        RequireKeysEqViolated,
+        RequireNeqViolated,
+        RequireKeysNeqViolated,
+        RequireGtViolated,
+        RequireGteViolated,
+        AccountDiscriminatorAlreadySet,
+        AccountDiscriminatorNotFound,
+        AccountDiscriminatorMismatch,
+        AccountDidNotDeserialize,
+        AccountDidNotSerialize,
+        AccountNotEnoughKeys,
+        AccountNotMutable,
+        AccountOwnedByWrongProgram,
+        InvalidProgramId,
+        InvalidProgramExecutable,
+        AccountNotSigner,
+        AccountNotSystemOwned,
+        AccountNotInitialized,
+        AccountNotProgramData,
+        AccountNotAssociatedTokenAccount,
+        AccountSysvarMismatch,
+        AccountReallocExceedsLimit,
+        AccountDuplicateReallocs,
+        DeclaredProgramIdMismatch,
+        TryingToInitPayerAsProgramAccount,
+        Deprecated,
+    ]
+};
+
+/// Resolves the error code to the Anchor framework [ErrorCode].
+fn anchor_error_code(code: u32) -> Option<ErrorCode> {
+    if code >= ERROR_CODE_OFFSET {
+        return None;
+    }
+    ANCHOR_ERROR_CODES
+        .iter()
+        .copied()
+        .find(|error_code| u32::from(*error_code) == code)
+}
+
+/// Asserts that the result is an error caused by the given Anchor error,
+/// either a framework [ErrorCode] or a variant of the program's `#[error_code]` enum.
+///
+/// The result error can be [ClientError] or [anyhow::Error].
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let result = escrow_instruction::exchange(&client, /* ... */).await;
+/// assert_anchor_error!(result, escrow::EscrowError::Unauthorized);
+///
+/// let result = escrow_instruction::cancel_escrow(&client, /* ... */).await;
+/// assert_anchor_error!(result, anchor_lang::error::ErrorCode::ConstraintSeeds);
+/// ```
+#[macro_export]
+macro_rules! assert_anchor_error {
+    ($result:expr, $error:expr $(,)?) => {{
+        let expected_code: u32 = ::core::convert::From::from($error);
+        match &$result {
+            Ok(_) => panic!(
+                "expected the `{}` error, but the transaction succeeded",
+                stringify!($error),
+            ),
+            Err(error) => match $crate::error_reporter::custom_program_error(error) {
+                Some(custom_error) if custom_error.code == expected_code => {}
+                Some(custom_error) => panic!(
+                    "expected the `{}` error ({}), but got: {}",
+                    stringify!($error),
+                    expected_code,
+                    custom_error,
+                ),
+                None => panic!(
+                    "expected the `{}` error ({}), but got: {}",
+                    stringify!($error),
+                    expected_code,
+                    error,
+                ),
+            },
+        }
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn anchor_error_codes_round_trip() {
+        for error_code in ANCHOR_ERROR_CODES {
+            let code = u32::from(*error_code);
+            let resolved = anchor_error_code(code).unwrap();
+            assert_eq!(u32::from(resolved), code);
+            assert_eq!(resolved.name(), format!("{error_code:?}"));
+        }
+        assert!(anchor_error_code(ERROR_CODE_OFFSET).is_none());
+    }
+}
@@ -39,6 +39,7 @@
 //!                                 "anchor_lang :: solana_program :: pubkey :: Pubkey",
 //!                             ),
 //!                         ],
+//!                         pdas: [],
 //!                     },
 //!                 ),
 //!                 (
@@ -65,6 +66,7 @@
 //!                                 "anchor_lang :: solana_program :: pubkey :: Pubkey",
 //!                             ),
 //!                         ],
+//!                         pdas: [],
 //!                     },
 //!                 ),
 //!                 (
@@ -86,6 +88,7 @@
 //!                                 "anchor_lang :: solana_program :: pubkey :: Pubkey",
 //!                             ),
 //!                         ],
+//!                         pdas: [],
 //!                     },
 //!                 ),
 //!             ],
@@ -136,6 +139,35 @@ pub struct IdlInstruction {
 pub struct IdlAccountGroup {
     pub name: IdlName,
     pub accounts: Vec<(String, String)>,
+    pub pdas: Vec<IdlPda>,
+}
+
+/// The account of [IdlAccountGroup] constrained with `#[account(seeds = [...], bump)]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdlPda {
+    pub account: String,
+    pub seeds: Vec<IdlSeed>,
+    /// The `bump = <expr>` expression or `None` when the canonical bump is found by Anchor.
+    pub bump: Option<String>,
+}
+
+/// The component of [IdlPda] seeds, the expressions are stored as token strings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IdlSeed {
+    /// The seed independent of the accounts and arguments, e.g. `b"escrow"`.
+    Const(String),
+    /// The key of the account from the same group, e.g. `authority.key().as_ref()`.
+    Account(String),
+    /// The instruction argument declared with `#[instruction(...)]`,
+    /// e.g. `amount.to_le_bytes().as_ref()`.
+    Argument {
+        name: String,
+        ty: String,
+        expr: String,
+    },
+    /// The other seed computed from the accounts, e.g. `escrow.mint.as_ref()`;
+    /// it's passed as bytes named after the expression (`escrow_mint`).
+    Bytes(String),
 }
 
 pub async fn parse_to_idl_program(name: String, code: &str) -> Result<IdlProgram, Error> {
@@ -143,6 +175,7 @@ pub async fn parse_to_idl_program(name: String, code: &str) -> Result<IdlProgram
     let mut mod_private = None::<syn::ItemMod>;
     let mut mod_instruction = None::<syn::ItemMod>;
     let mut account_mods = Vec::<syn::ItemMod>::new();
+    let mut account_structs = Vec::<syn::ItemStruct>::new();
 
     for item in syn::parse_file(code)?.items.into_iter() {
         match item {
@@ -152,8 +185,12 @@ pub async fn parse_to_idl_program(name: String, code: &str) -> Result<IdlProgram
             syn::Item::Mod(item_mod) => match item_mod.ident.to_string().as_str() {
                 "__private" => mod_private = Some(item_mod),
                 "instruction" => mod_instruction = Some(item_mod),
-                _ => set_account_modules(&mut account_mods, item_mod),
+                _ => {
+                    set_account_structs(&mut account_structs, &item_mod);
+                    set_account_modules(&mut account_mods, item_mod)
+                }
             },
+            syn::Item::Struct(item_struct) => account_structs.push(item_struct),
             _ => (),
         }
     }
@@ -321,6 +358,7 @@ pub async fn parse_to_idl_program(name: String, code: &str) -> Result<IdlProgram
                     upper_camel_case: account_group_name,
                 },
                 accounts: Vec::new(),
+                pdas: Vec::new(),
             };
             Some((idl_instruction, idl_account))
         })
@@ -456,6 +494,28 @@ pub async fn parse_to_idl_program(name: String, code: &str) -> Result<IdlProgram
         }
     }
 
+    // ------ get pdas ------
+
+    // input example:
+    // ```
+    // pub struct Exchange<'info> {
+    //     /// CHECK: ...
+    //     #[account(seeds = [b"escrow"], bump)]
+    //     pub pda_account: AccountInfo<'info>,
+    // ```
+
+    for account_struct in account_structs {
+        let pdas = parse_pdas(&account_struct);
+        if pdas.is_empty() {
+            continue;
+        }
+        for (_, idl_account_group) in &mut instruction_account_pairs {
+            if account_struct.ident == idl_account_group.name.upper_camel_case {
+                idl_account_group.pdas = pdas.clone();
+            }
+        }
+    }
+
     // ------ // ------
 
     Ok(IdlProgram {
@@ -490,3 +550,185 @@ fn set_account_modules(account_modules: &mut Vec<syn::ItemMod>, item_module: syn
         }
     }
 }
+
+fn set_account_structs(account_structs: &mut Vec<syn::ItemStruct>, item_module: &syn::ItemMod) {
+    if item_module
+        .ident
+        .to_string()
+        .starts_with(ACCOUNT_MOD_PREFIX)
+    {
+        return;
+    }
+    for item in item_module.content.iter().flat_map(|(_, items)| items) {
+        match item {
+            syn::Item::Struct(item_struct) => account_structs.push(item_struct.clone()),
+            syn::Item::Mod(nested_module) => set_account_structs(account_structs, nested_module),
+            _ => (),
+        }
+    }
+}
+
+/// A constraint inside `#[account(...)]`, e.g. `mut`, `seeds = [..]` or `has_one = a @ Error`.
+struct AccountConstraint {
+    name: String,
+    value: Option<syn::Expr>,
+}
+
+impl syn::parse::Parse for AccountConstraint {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        use syn::ext::IdentExt;
+
+        let mut name = syn::Ident::parse_any(input)?.to_string();
+        while input.peek(syn::Token![::]) {
+            input.parse::<syn::Token![::]>()?;
+            name = format!("{name}::{}", syn::Ident::parse_any(input)?);
+        }
+        let value = if input.peek(syn::Token![=]) {
+            input.parse::<syn::Token![=]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        // the custom error, e.g. `@ ErrorCode::Unauthorized`
+        if input.peek(syn::Token![@]) {
+            input.parse::<syn::Token![@]>()?;
+            input.parse::<syn::Expr>()?;
+        }
+        Ok(Self { name, value })
+    }
+}
+
+fn parse_pdas(account_struct: &syn::ItemStruct) -> Vec<IdlPda> {
+    let fields = match &account_struct.fields {
+        syn::Fields::Named(fields_named) => &fields_named.named,
+        _ => return Vec::new(),
+    };
+    let accounts = fields
+        .iter()
+        .filter_map(|field| Some(field.ident.as_ref()?.to_string()))
+        .collect::<Vec<_>>();
+
+    // `#[instruction(initializer_amount : u64)]`
+    let arguments = account_struct
+        .attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("instruction"))
+        .filter_map(|attr| {
+            attr.parse_args_with(
+                syn::punctuated::Punctuated::<syn::FnArg, syn::Token![,]>::parse_terminated,
+            )
+            .ok()
+        })
+        .flatten()
+        .filter_map(|argument| match argument {
+            syn::FnArg::Typed(pat_type) => Some((
+                pat_type.pat.into_token_stream().to_string(),
+                pat_type.ty.into_token_stream().to_string(),
+            )),
+            syn::FnArg::Receiver(_) => None,
+        })
+        .collect::<Vec<_>>();
+
+    fields
+        .iter()
+        .filter_map(|field| {
+            // unknown constraints are skipped instead of failing the client generation
+            let constraints = field
+                .attrs
+                .iter()
+                .filter(|attr| attr.path.is_ident("account"))
+                .filter_map(|attr| {
+                    attr.parse_args_with(
+                        syn::punctuated::Punctuated::<AccountConstraint, syn::Token![,]>::parse_terminated,
+                    )
+                    .ok()
+                })
+                .flatten()
+                .collect::<Vec<_>>();
+            // the addresses derived from another program are not supported
+            if constraints
+                .iter()
+                .any(|constraint| constraint.name == "seeds::program")
+            {
+                None?
+            }
+            let seeds = constraints
+                .iter()
+                .find_map(|constraint| match (&*constraint.name, &constraint.value) {
+                    ("seeds", Some(syn::Expr::Array(seeds))) => Some(seeds),
+                    _ => None,
+                })?;
+            let bump = constraints
+                .iter()
+                .find(|constraint| constraint.name == "bump")
+                .and_then(|constraint| constraint.value.as_ref())
+                .map(|bump| bump.into_token_stream().to_string());
+            Some(IdlPda {
+                account: field.ident.as_ref()?.to_string(),
+                seeds: seeds
+                    .elems
+                    .iter()
+                    .map(|seed| parse_seed(seed, &accounts, &arguments))
+                    .collect(),
+                bump,
+            })
+        })
+        .collect()
+}
+
+fn parse_seed(seed: &syn::Expr, accounts: &[String], arguments: &[(String, String)]) -> IdlSeed {
+    let expr = seed.into_token_stream().to_string();
+    let mut chain = Vec::new();
+    let root = match seed_root(seed, &mut chain) {
+        syn::Expr::Path(expr_path) => match expr_path.path.get_ident() {
+            Some(ident) => ident.to_string(),
+            None => return IdlSeed::Const(expr),
+        },
+        _ => return IdlSeed::Const(expr),
+    };
+    if let Some((name, ty)) = arguments.iter().find(|(name, _)| *name == root) {
+        return IdlSeed::Argument {
+            name: name.clone(),
+            ty: ty.clone(),
+            expr,
+        };
+    }
+    if !accounts.contains(&root) {
+        return IdlSeed::Const(expr);
+    }
+    // `authority.key().as_ref()` or `authority.key.as_ref()`
+    if chain.first().map(String::as_str) == Some("key") {
+        return IdlSeed::Account(root);
+    }
+    let name = std::iter::once(root)
+        .chain(
+            chain
+                .into_iter()
+                .take_while(|segment| !segment.starts_with("as_") && !segment.starts_with("to_"))
+                .filter(|segment| segment != "key"),
+        )
+        .collect::<Vec<_>>()
+        .join("_");
+    IdlSeed::Bytes(name)
+}
+
+/// Returns the innermost receiver of the seed expression, e.g. `authority` for `&authority.key().as_ref()`,
+/// and collects the names of the fields and methods applied to it.
+fn seed_root<'a>(seed: &'a syn::Expr, chain: &mut Vec<String>) -> &'a syn::Expr {
+    let (inner, segment) = match seed {
+        syn::Expr::MethodCall(method_call) => {
+            (&*method_call.receiver, Some(method_call.method.to_string()))
+        }
+        syn::Expr::Field(field) => (
+            &*field.base,
+            Some(field.member.to_token_stream().to_string()),
+        ),
+        syn::Expr::Reference(reference) => (&*reference.expr, None),
+        syn::Expr::Paren(paren) => (&*paren.expr, None),
+        syn::Expr::Unary(unary) => (&*unary.expr, None),
+        _ => return seed,
+    };
+    let root = seed_root(inner, chain);
+    chain.extend(segment);
+    root
+}
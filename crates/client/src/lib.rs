@@ -15,6 +15,7 @@ pub use anchor_client::{
     },
     ClientError,
 };
+pub use anchor_syn::idl::Idl as AnchorIdl;
 pub use anyhow::{self, Error};
 
 #[cfg(feature = "fuzzing")]
@@ -35,20 +36,60 @@ pub mod fuzzing {
 pub use futures::{self, FutureExt};
 pub use rstest::*;
 pub use serial_test;
+pub use solana_program_test::{self, processor, ProgramTest};
 pub use solana_transaction_status::EncodedConfirmedTransactionWithStatusMeta;
+pub use spl_token;
+pub use spl_token_2022;
 pub use tokio;
 
 pub use trdelnik_test::trdelnik_test;
 
 mod config;
 
+mod backend;
+
 mod client;
 pub use client::Client;
 pub use client::PrintableTransaction;
 
+mod send_options;
+pub use send_options::SendOptions;
+
+mod token_extensions;
+pub use token_extensions::MintExtension;
+
+mod transaction_outcome;
+pub use transaction_outcome::{InstructionNode, TransactionOutcome};
+
+mod account_snapshot;
+pub use account_snapshot::{
+    AccountDiff, AccountSnapshot, AccountState, AccountStatus, ByteChange, Change, FieldChange,
+    SnapshotAccount, SnapshotDiff,
+};
+
+mod offline_transaction;
+pub use offline_transaction::OfflineTransaction;
+
+mod simulation_outcome;
+pub use simulation_outcome::SimulationOutcome;
+
 mod reader;
 pub use reader::Reader;
 
+mod differential;
+pub use differential::{add_program_binary, DifferentialTest, Divergence, DivergenceKind};
+
+mod upgrade_check;
+pub use upgrade_check::{AccountDecodeFailure, AccountTypes, UpgradeReport};
+
+mod lock_contention;
+pub use lock_contention::{Conflict, ContentionAnalyzer, ContentionReport};
+
+mod compute_units;
+pub use compute_units::{
+    ComputeUnitBaseline, ComputeUnitChange, ComputeUnitReport, CU_BASELINE_FILE,
+};
+
 mod commander;
 pub use commander::{Commander, LocalnetHandle};
 
@@ -0,0 +1,267 @@
+use crate::{transaction_outcome::parse_pubkey, TransactionOutcome};
+use anchor_client::{
+    solana_sdk::{instruction::Instruction, pubkey::Pubkey},
+    ClientError as Error,
+};
+use fehler::throws;
+use solana_transaction_status::UiLoadedAddresses;
+use std::{collections::BTreeSet, fmt};
+
+/// Analyzes which instructions or transactions conflict on the write locks of their accounts
+/// and how many of them the Sealevel runtime could execute in parallel.
+///
+/// Two items conflict when one of them writes an account the other one reads or writes.
+///
+/// _Note_: The fee payer is write-locked by every transaction. It is not part of
+/// the instructions, but it is part of the observed transactions, so the transactions
+/// paid by the same payer always conflict.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let report = ContentionAnalyzer::new()
+///     .with_instruction("alice", escrow_instruction::exchange_ix(/* ... */))
+///     .with_instruction("bob", escrow_instruction::exchange_ix(/* ... */))
+///     .analyze();
+/// println!("{report}");
+/// assert_eq!(report.batches.len(), 1);
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct ContentionAnalyzer {
+    items: Vec<LockedItem>,
+}
+
+/// The accounts locked by an analyzed instruction or transaction.
+#[derive(Debug, Clone)]
+struct LockedItem {
+    name: String,
+    writable: BTreeSet<Pubkey>,
+    readonly: BTreeSet<Pubkey>,
+}
+
+impl ContentionAnalyzer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the instruction, it locks its writable and readonly accounts.
+    pub fn with_instruction(self, name: impl Into<String>, instruction: Instruction) -> Self {
+        self.with_transaction(name, &[instruction])
+    }
+
+    /// Adds the transaction with the instructions, it locks the accounts of all of them.
+    pub fn with_transaction(
+        mut self,
+        name: impl Into<String>,
+        instructions: &[Instruction],
+    ) -> Self {
+        let mut writable = BTreeSet::new();
+        let mut readonly = BTreeSet::new();
+        for instruction in instructions {
+            // the invoked programs are locked as readonly
+            readonly.insert(instruction.program_id);
+            for account in &instruction.accounts {
+                if account.is_writable {
+                    writable.insert(account.pubkey);
+                } else {
+                    readonly.insert(account.pubkey);
+                }
+            }
+        }
+        self.items.push(LockedItem {
+            name: name.into(),
+            readonly: readonly.difference(&writable).copied().collect(),
+            writable,
+        });
+        self
+    }
+
+    /// Adds the transaction observed during a test run, e.g. returned by [Client::send_many](crate::Client::send_many).
+    ///
+    /// # Errors
+    ///
+    /// It fails when the transaction cannot be decoded.
+    #[throws]
+    pub fn with_outcome(mut self, name: impl Into<String>, outcome: &TransactionOutcome) -> Self {
+        let transaction = &outcome.transaction.transaction;
+        let decoded = transaction
+            .transaction
+            .decode()
+            .ok_or_else(|| Error::LogParseError("Transaction decoding failed".to_string()))?;
+        let mut writable = BTreeSet::new();
+        let mut readonly = BTreeSet::new();
+        for (index, account) in decoded.message.static_account_keys().iter().enumerate() {
+            if decoded.message.is_maybe_writable(index) {
+                writable.insert(*account);
+            } else {
+                readonly.insert(*account);
+            }
+        }
+        let loaded_addresses = transaction
+            .meta
+            .as_ref()
+            .and_then(|meta| Option::<UiLoadedAddresses>::from(meta.loaded_addresses.clone()));
+        if let Some(loaded_addresses) = loaded_addresses {
+            for address in &loaded_addresses.writable {
+                writable.insert(parse_pubkey(address)?);
+            }
+            for address in &loaded_addresses.readonly {
+                readonly.insert(parse_pubkey(address)?);
+            }
+        }
+        self.items.push(LockedItem {
+            name: name.into(),
+            writable,
+            readonly,
+        });
+        self
+    }
+
+    /// Computes the conflict graph and the batches of the items that can run in parallel.
+    pub fn analyze(&self) -> ContentionReport {
+        let mut conflicts = Vec::new();
+        for (first, first_item) in self.items.iter().enumerate() {
+            for (second, second_item) in self.items.iter().enumerate().skip(first + 1) {
+                let accounts = first_item
+                    .writable
+                    .iter()
+                    .filter(|account| {
+                        second_item.writable.contains(account)
+                            || second_item.readonly.contains(account)
+                    })
+                    .chain(
+                        second_item
+                            .writable
+                            .iter()
+                            .filter(|account| first_item.readonly.contains(account)),
+                    )
+                    .copied()
+                    .collect::<BTreeSet<_>>();
+                if !accounts.is_empty() {
+                    conflicts.push(Conflict {
+                        first,
+                        second,
+                        accounts: accounts.into_iter().collect(),
+                    });
+                }
+            }
+        }
+
+        // greedy coloring in the order of the items, like the runtime scheduling them in order
+        let mut batches: Vec<Vec<usize>> = Vec::new();
+        for item in 0..self.items.len() {
+            let conflicts_with = |other: &usize| {
+                conflicts.iter().any(|conflict| {
+                    (conflict.first, conflict.second) == (*other, item)
+                        || (conflict.first, conflict.second) == (item, *other)
+                })
+            };
+            match batches
+                .iter_mut()
+                .find(|batch| !batch.iter().any(conflicts_with))
+            {
+                Some(batch) => batch.push(item),
+                None => batches.push(vec![item]),
+            }
+        }
+
+        ContentionReport {
+            items: self.items.iter().map(|item| item.name.clone()).collect(),
+            conflicts,
+            batches,
+        }
+    }
+}
+
+/// The conflict of two analyzed items, see [ContentionAnalyzer].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    /// The index of the first item.
+    pub first: usize,
+    /// The index of the second item.
+    pub second: usize,
+    /// The accounts written by one item and read or written by the other one.
+    pub accounts: Vec<Pubkey>,
+}
+
+/// The conflict graph of the analyzed items and the estimate of their parallelism.
+///
+/// It is printed as the list of conflicts followed by the batches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentionReport {
+    /// The names of the analyzed items.
+    pub items: Vec<String>,
+    /// The edges of the conflict graph.
+    pub conflicts: Vec<Conflict>,
+    /// The items without mutual conflicts, every batch can run in parallel.
+    ///
+    /// The batches run one after another, so their count estimates
+    /// how many sequential steps the items take.
+    pub batches: Vec<Vec<usize>>,
+}
+
+impl ContentionReport {
+    /// Returns the accounts conflicting between the items, most contended first,
+    /// with the number of conflicts they cause.
+    pub fn hot_accounts(&self) -> Vec<(Pubkey, usize)> {
+        let mut counts: Vec<(Pubkey, usize)> = Vec::new();
+        for account in self
+            .conflicts
+            .iter()
+            .flat_map(|conflict| &conflict.accounts)
+        {
+            match counts.iter_mut().find(|(hot, _)| hot == account) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((*account, 1)),
+            }
+        }
+        counts.sort_by(|first, second| second.1.cmp(&first.1));
+        counts
+    }
+
+    /// The average number of items executed in parallel, i.e. the items per batch.
+    pub fn parallelism(&self) -> f64 {
+        match self.batches.len() {
+            0 => 0.0,
+            batches => self.items.len() as f64 / batches as f64,
+        }
+    }
+}
+
+impl fmt::Display for ContentionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Conflicts:")?;
+        if self.conflicts.is_empty() {
+            writeln!(f, "  none")?;
+        }
+        for conflict in &self.conflicts {
+            let accounts = conflict
+                .accounts
+                .iter()
+                .map(Pubkey::to_string)
+                .collect::<Vec<_>>()
+                .join(", ");
+            writeln!(
+                f,
+                "  {} <-> {}: {}",
+                self.items[conflict.first], self.items[conflict.second], accounts
+            )?;
+        }
+        writeln!(f, "Batches:")?;
+        for (index, batch) in self.batches.iter().enumerate() {
+            let names = batch
+                .iter()
+                .map(|item| self.items[*item].as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            writeln!(f, "  {}: {}", index + 1, names)?;
+        }
+        write!(
+            f,
+            "{} item(s) in {} sequential batch(es), parallelism {:.2}",
+            self.items.len(),
+            self.batches.len(),
+            self.parallelism()
+        )
+    }
+}
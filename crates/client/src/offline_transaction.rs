@@ -0,0 +1,163 @@
+use anchor_client::{
+    solana_client::client_error::ClientErrorKind,
+    solana_sdk::{
+        pubkey::Pubkey, signature::Signature, signer::keypair::Keypair, transaction::Transaction,
+    },
+    ClientError as Error,
+};
+use fehler::{throw, throws};
+use solana_transaction_status::{EncodedTransaction, TransactionBinaryEncoding};
+
+/// A transaction signed by more signers one by one, e.g. by a multisig or offline signers.
+///
+/// It is usually built on a durable nonce with [Client::nonce_transaction](crate::Client::nonce_transaction),
+/// so it does not expire while the signatures are collected.
+/// The signers add their signatures to the serialized copies, which are merged and
+/// submitted with [Client::send_offline_transaction](crate::Client::send_offline_transaction).
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let mut transaction = client
+///     .nonce_transaction(&instructions, nonce.pubkey(), alice.pubkey(), alice.pubkey())
+///     .await?;
+/// transaction.sign(&[&alice])?;
+/// let serialized = transaction.to_base64()?;
+///
+/// // the offline signer
+/// let mut bobs_copy = OfflineTransaction::from_base64(&serialized)?;
+/// bobs_copy.sign(&[&bob])?;
+///
+/// transaction.merge(&OfflineTransaction::from_base64(&bobs_copy.to_base64()?)?)?;
+/// client.send_offline_transaction(&transaction).await?;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OfflineTransaction {
+    transaction: Transaction,
+}
+
+impl OfflineTransaction {
+    /// Wraps the transaction; its message must not change after the first signature.
+    pub fn new(transaction: Transaction) -> Self {
+        Self { transaction }
+    }
+
+    /// Returns the wrapped transaction.
+    pub fn transaction(&self) -> &Transaction {
+        &self.transaction
+    }
+
+    /// Unwraps the transaction.
+    pub fn into_transaction(self) -> Transaction {
+        self.transaction
+    }
+
+    /// Adds the signatures of the `signers` and keeps the other signatures.
+    ///
+    /// # Errors
+    ///
+    /// It fails when a signer is not required by the transaction.
+    #[throws]
+    pub fn sign(&mut self, signers: &[&Keypair]) {
+        let blockhash = self.transaction.message.recent_blockhash;
+        self.transaction
+            .try_partial_sign(signers, blockhash)
+            .map_err(|error| {
+                Error::SolanaClientError(ClientErrorKind::SigningError(error).into())
+            })?
+    }
+
+    /// Returns the required signers whose signatures are missing.
+    pub fn missing_signers(&self) -> Vec<Pubkey> {
+        self.signer_keys()
+            .zip(&self.transaction.signatures)
+            .filter(|(_, signature)| **signature == Signature::default())
+            .map(|(signer, _)| *signer)
+            .collect()
+    }
+
+    /// Returns `true` when all required signatures are present.
+    pub fn is_fully_signed(&self) -> bool {
+        self.missing_signers().is_empty()
+    }
+
+    /// Copies the signatures missing in this transaction from the `other` copy.
+    ///
+    /// # Errors
+    ///
+    /// It fails when the copies differ in their messages, i.e. they are different transactions.
+    #[throws]
+    pub fn merge(&mut self, other: &OfflineTransaction) {
+        if self.transaction.message != other.transaction.message {
+            throw!(Error::SolanaClientError(
+                ClientErrorKind::Custom(
+                    "cannot merge the signatures of different transactions".to_string()
+                )
+                .into()
+            ));
+        }
+        for (signature, other_signature) in self
+            .transaction
+            .signatures
+            .iter_mut()
+            .zip(&other.transaction.signatures)
+        {
+            if *signature == Signature::default() {
+                *signature = *other_signature;
+            }
+        }
+    }
+
+    /// Serializes the transaction with its signatures to base58.
+    #[throws]
+    pub fn to_base58(&self) -> String {
+        bs58::encode(self.serialize()?).into_string()
+    }
+
+    /// Serializes the transaction with its signatures to base64.
+    #[throws]
+    pub fn to_base64(&self) -> String {
+        base64::encode(self.serialize()?)
+    }
+
+    /// Deserializes the transaction serialized with [OfflineTransaction::to_base58].
+    #[throws]
+    pub fn from_base58(encoded: &str) -> Self {
+        Self::decode(encoded, TransactionBinaryEncoding::Base58)?
+    }
+
+    /// Deserializes the transaction serialized with [OfflineTransaction::to_base64].
+    #[throws]
+    pub fn from_base64(encoded: &str) -> Self {
+        Self::decode(encoded, TransactionBinaryEncoding::Base64)?
+    }
+
+    fn signer_keys(&self) -> impl Iterator<Item = &Pubkey> {
+        self.transaction
+            .message
+            .account_keys
+            .iter()
+            .take(self.transaction.message.header.num_required_signatures as usize)
+    }
+
+    #[throws]
+    fn serialize(&self) -> Vec<u8> {
+        bincode::serialize(&self.transaction).map_err(|error| {
+            Error::SolanaClientError(ClientErrorKind::Custom(error.to_string()).into())
+        })?
+    }
+
+    #[throws]
+    fn decode(encoded: &str, encoding: TransactionBinaryEncoding) -> Self {
+        let transaction = EncodedTransaction::Binary(encoded.to_string(), encoding)
+            .decode()
+            .and_then(|transaction| transaction.into_legacy_transaction())
+            .ok_or_else(|| {
+                Error::SolanaClientError(
+                    ClientErrorKind::Custom("transaction deserialization failed".to_string())
+                        .into(),
+                )
+            })?;
+        Self::new(transaction)
+    }
+}
@@ -1,4 +1,4 @@
-use crate::idl::Idl;
+use crate::idl::{Idl, IdlPda, IdlSeed};
 use quote::{format_ident, ToTokens};
 use syn::{parse_quote, parse_str};
 
@@ -17,6 +17,35 @@ pub fn generate_source_code(idl: Idl, use_modules: &[syn::ItemUse]) -> String {
             let module_name: syn::Ident = parse_str(&program_name).unwrap();
             let pubkey_bytes: syn::ExprArray = parse_str(&idl_program.id).unwrap();
 
+            let pdas = idl_program
+                .instruction_account_pairs
+                .iter()
+                .flat_map(|(_, idl_account_group)| {
+                    idl_account_group
+                        .pdas
+                        .iter()
+                        .map(|pda| (idl_account_group.name.snake_case.as_str(), pda))
+                })
+                .collect::<Vec<_>>();
+            let mut pda_fn_names = Vec::new();
+            let mut pda_fns = Vec::new();
+            for (account_group_name, pda) in &pdas {
+                // the same account derived from different seeds in more groups is prefixed with the group
+                let ambiguous = pdas
+                    .iter()
+                    .any(|(_, other)| other.account == pda.account && other.seeds != pda.seeds);
+                let pda_fn_name = if ambiguous {
+                    format_ident!("find_{}_{}_address", account_group_name, pda.account)
+                } else {
+                    format_ident!("find_{}_address", pda.account)
+                };
+                if pda_fn_names.contains(&pda_fn_name) {
+                    continue;
+                }
+                pda_fns.push(generate_pda_fn(&pda_fn_name, pda, &module_name));
+                pda_fn_names.push(pda_fn_name);
+            }
+
             let instructions = idl_program
                 .instruction_account_pairs
                 .into_iter()
@@ -29,6 +58,9 @@ pub fn generate_source_code(idl: Idl, use_modules: &[syn::ItemUse]) -> String {
                             parse_str(&idl_instruction.name.upper_camel_case).unwrap();
                         let account_struct_name: syn::Ident =
                             parse_str(&idl_account_group.name.upper_camel_case).unwrap();
+                        let instruction_with_options_name: syn::Ident =
+                            parse_str(&(idl_instruction.name.snake_case.clone() + "_with_options"))
+                                .unwrap();
                         let instruction_name: syn::Ident =
                             parse_str(&(idl_instruction.name.snake_case + "_ix")).unwrap();
 
@@ -82,7 +114,7 @@ pub fn generate_source_code(idl: Idl, use_modules: &[syn::ItemUse]) -> String {
                                 #(#parameters,)*
                                 #(#accounts,)*
                                 signers: impl IntoIterator<Item = Keypair> + Send + 'static,
-                            ) -> Result<EncodedConfirmedTransactionWithStatusMeta, ClientError> {
+                            ) -> Result<TransactionOutcome, ClientError> {
                                 Ok(client.send_instruction(
                                     PROGRAM_ID,
                                     #module_name::instruction::#instruction_struct_name {
@@ -96,6 +128,28 @@ pub fn generate_source_code(idl: Idl, use_modules: &[syn::ItemUse]) -> String {
                             }
                         };
 
+                        let instruction_with_options: syn::ItemFn = parse_quote! {
+                            pub async fn #instruction_with_options_name(
+                                client: &Client,
+                                #(#parameters,)*
+                                #(#accounts,)*
+                                signers: impl IntoIterator<Item = Keypair> + Send + 'static,
+                                options: &SendOptions,
+                            ) -> Result<TransactionOutcome, ClientError> {
+                                Ok(client.send_instruction_with_options(
+                                    PROGRAM_ID,
+                                    #module_name::instruction::#instruction_struct_name {
+                                        #(#field_parameters,)*
+                                    },
+                                    #module_name::accounts::#account_struct_name {
+                                        #(#field_accounts,)*
+                                    },
+                                    signers,
+                                    options,
+                                ).await?)
+                            }
+                        };
+
                         let instruction_raw: syn::ItemFn = parse_quote! {
                             pub  fn #instruction_name(
                                 #(#parameters,)*
@@ -114,6 +168,7 @@ pub fn generate_source_code(idl: Idl, use_modules: &[syn::ItemUse]) -> String {
                         };
 
                         instructions.push(instruction);
+                        instructions.push(instruction_with_options);
                         instructions.push(instruction_raw);
                         instructions
                     },
@@ -125,6 +180,7 @@ pub fn generate_source_code(idl: Idl, use_modules: &[syn::ItemUse]) -> String {
                     #(#use_modules)*
                     pub static PROGRAM_ID: Pubkey = Pubkey::new_from_array(#pubkey_bytes);
                     #(#instructions)*
+                    #(#pda_fns)*
                 }
             };
             program_module.into_token_stream().to_string()
@@ -133,3 +189,74 @@ pub fn generate_source_code(idl: Idl, use_modules: &[syn::ItemUse]) -> String {
     output.push_str(&code);
     output
 }
+
+/// Generates `find_<account>_address` with the dynamic seeds as parameters.
+fn generate_pda_fn(name: &syn::Ident, pda: &IdlPda, module_name: &syn::Ident) -> syn::ItemFn {
+    let mut parameters = Vec::<syn::FnArg>::new();
+    let mut seeds = Vec::<syn::Expr>::new();
+    for seed in &pda.seeds {
+        let (parameter, seed): (Option<syn::FnArg>, syn::Expr) = match seed {
+            IdlSeed::Const(expr) => {
+                let mut expr: syn::Expr = parse_str(expr).unwrap();
+                set_program_path(&mut expr, module_name);
+                (None, expr)
+            }
+            IdlSeed::Account(name) => {
+                let name: syn::Ident = parse_str(name).unwrap();
+                (
+                    Some(parse_quote!(#name: Pubkey)),
+                    parse_quote!(#name.as_ref()),
+                )
+            }
+            IdlSeed::Argument { name, ty, expr } => {
+                let name: syn::Ident = parse_str(name).unwrap();
+                let ty: syn::Type = parse_str(ty).unwrap();
+                (Some(parse_quote!(#name: #ty)), parse_str(expr).unwrap())
+            }
+            IdlSeed::Bytes(name) => {
+                let name: syn::Ident = parse_str(name).unwrap();
+                (Some(parse_quote!(#name: &[u8])), parse_quote!(#name))
+            }
+        };
+        if let Some(parameter) = parameter {
+            if !parameters.contains(&parameter) {
+                parameters.push(parameter);
+            }
+        }
+        seeds.push(seed);
+    }
+    parse_quote! {
+        pub fn #name(#(#parameters,)*) -> (Pubkey, u8) {
+            Pubkey::find_program_address(&[#(#seeds,)*], &PROGRAM_ID)
+        }
+    }
+}
+
+/// Points the constants used as seeds to the program crate,
+/// e.g. `crate::SEED.as_ref()` or `SEED.as_ref()` to `escrow::SEED.as_ref()`.
+fn set_program_path(seed: &mut syn::Expr, module_name: &syn::Ident) {
+    match seed {
+        syn::Expr::MethodCall(method_call) => {
+            set_program_path(&mut method_call.receiver, module_name)
+        }
+        syn::Expr::Field(field) => set_program_path(&mut field.base, module_name),
+        syn::Expr::Reference(reference) => set_program_path(&mut reference.expr, module_name),
+        syn::Expr::Paren(paren) => set_program_path(&mut paren.expr, module_name),
+        syn::Expr::Unary(unary) => set_program_path(&mut unary.expr, module_name),
+        syn::Expr::Path(expr_path) => {
+            let segments = &mut expr_path.path.segments;
+            if segments[0].ident == "crate" {
+                segments[0].ident = module_name.clone();
+            } else if segments.len() == 1
+                && segments[0]
+                    .ident
+                    .to_string()
+                    .chars()
+                    .all(|c| !c.is_lowercase())
+            {
+                segments.insert(0, module_name.clone().into());
+            }
+        }
+        _ => (),
+    }
+}
@@ -1,12 +1,18 @@
+use crate::Commander;
 use anchor_client::solana_sdk::{
     pubkey::{ParsePubkeyError, Pubkey},
     signer::keypair::Keypair,
 };
 use ed25519_dalek::SignatureError;
-use fehler::throws;
-use std::{borrow::Cow, io, str::FromStr};
+use fehler::{throw, throws};
+use std::{
+    borrow::Cow,
+    io,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 use thiserror::Error;
-use tokio::fs;
+use tokio::{fs, process::Command};
 
 #[derive(Error, Debug)]
 pub enum Error {
@@ -18,6 +24,10 @@ pub enum Error {
     Pubkey(#[from] ParsePubkeyError),
     #[error("keypair parsing failed")]
     Keypair(#[from] SignatureError),
+    #[error("checking out the git revision '{0}' failed")]
+    GitCheckout(String),
+    #[error("building the programs at the git revision '{0}' failed")]
+    BuildPrograms(String),
 }
 
 /// `Reader` allows you to read [Pubkey], [Keypair] and other entities from files.
@@ -73,6 +83,102 @@ impl Reader {
     pub async fn program_data(&self, name: &str) -> Vec<u8> {
         fs::read(format!("{}target/deploy/{}.so", self.root, name)).await?
     }
+
+    /// Reads program data from the `.so` file at the provided path,
+    /// e.g. from a binary of a previous release.
+    ///
+    /// # Errors
+    ///
+    /// It fails when the requested file does not exist or it is not readable.
+    #[throws]
+    pub async fn program_data_from_path(&self, path: impl AsRef<Path>) -> Vec<u8> {
+        fs::read(path).await?
+    }
+
+    /// Builds the programs at the git `revision` and reads the program data
+    /// from `target/deploy/[name].so` of the build.
+    ///
+    /// The revision is checked out by [Reader::checkout_revision]. The programs are built
+    /// in the Anchor workspace of the checkout the same way as by [Commander::build_programs](crate::Commander::build_programs).
+    ///
+    /// # Errors
+    ///
+    /// It fails when:
+    /// - the revision cannot be resolved or checked out.
+    /// - the programs cannot be built with `cargo build-bpf`.
+    /// - the built program does not exist or it is not readable.
+    #[throws]
+    pub async fn program_data_at_revision(&self, name: &str, revision: &str) -> Vec<u8> {
+        let workspace = self.checkout_revision(revision).await?;
+
+        let success = Commander::build_programs_command()
+            .current_dir(&workspace)
+            .spawn()?
+            .wait()
+            .await?
+            .success();
+        if !success {
+            throw!(Error::BuildPrograms(revision.to_string()));
+        }
+
+        fs::read(workspace.join(format!("target/deploy/{name}.so"))).await?
+    }
+
+    /// Checks out the git `revision` and returns the path of the Anchor workspace (the `root`) in the checkout.
+    ///
+    /// The revision (e.g. a tag, a branch or `HEAD~1`) is resolved to its commit and checked out
+    /// to the git worktree `[root]target/trdelnik/revisions/[commit]`. The worktree is kept,
+    /// so the subsequent calls for the same commit reuse it. The whole repository is checked out,
+    /// so the returned path points to the same subdirectory as the `root` when the Anchor workspace
+    /// is not the top level of the git repository.
+    ///
+    /// # Errors
+    ///
+    /// It fails when the revision cannot be resolved or checked out.
+    #[throws]
+    pub async fn checkout_revision(&self, revision: &str) -> PathBuf {
+        let root = match self.root.as_ref() {
+            "" => ".",
+            root => root,
+        };
+        let git = |args: &[&str]| {
+            let mut command = Command::new("git");
+            command.args(args).current_dir(root);
+            command
+        };
+
+        let output = git(&["rev-parse", "--verify", &format!("{revision}^{{commit}}")])
+            .output()
+            .await?;
+        if !output.status.success() {
+            throw!(Error::GitCheckout(revision.to_string()));
+        }
+        let commit = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+        // the path of the root relative to the top level of the repository, e.g. `examples/escrow/`
+        let output = git(&["rev-parse", "--show-prefix"]).output().await?;
+        if !output.status.success() {
+            throw!(Error::GitCheckout(revision.to_string()));
+        }
+        let prefix = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+        let worktree_path = format!("target/trdelnik/revisions/{commit}");
+        let worktree = Path::new(root).join(&worktree_path);
+        if fs::metadata(&worktree).await.is_err() {
+            let success = git(&["worktree", "add", "--detach", "--force"])
+                .arg(&worktree_path)
+                .arg(&commit)
+                .spawn()?
+                .wait()
+                .await?
+                .success();
+            if !success {
+                throw!(Error::GitCheckout(revision.to_string()));
+            }
+        }
+
+        worktree.join(prefix)
+    }
 }
 
 impl Default for Reader {
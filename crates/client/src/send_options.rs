@@ -0,0 +1,66 @@
+use anchor_client::solana_sdk::{
+    commitment_config::CommitmentConfig, compute_budget::ComputeBudgetInstruction,
+    instruction::Instruction,
+};
+
+/// Options applied to the transactions sent by [Client](crate::Client).
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let client = Client::new(system_keypair(0)).with_send_options(SendOptions {
+///     compute_unit_limit: Some(200_000),
+///     compute_unit_price: Some(1_000),
+///     ..SendOptions::default()
+/// });
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SendOptions {
+    /// The compute unit limit of the transaction, see `ComputeBudgetInstruction::SetComputeUnitLimit`.
+    pub compute_unit_limit: Option<u32>,
+    /// The compute unit price in micro-lamports, see `ComputeBudgetInstruction::SetComputeUnitPrice`.
+    pub compute_unit_price: Option<u64>,
+    /// The heap frame size in bytes, see `ComputeBudgetInstruction::RequestHeapFrame`.
+    /// It has to be a multiple of 1024.
+    pub heap_frame_size: Option<u32>,
+    /// The commitment the transaction is confirmed with; `confirmed` by default.
+    ///
+    /// The transactions confirmed with the `processed` commitment are fetched with the `confirmed` one.
+    pub commitment: CommitmentConfig,
+    /// Sends the transaction without the preflight simulation.
+    pub skip_preflight: bool,
+    /// The maximum number of times the RPC node retries sending the transaction to the leader.
+    pub max_retries: Option<usize>,
+}
+
+impl Default for SendOptions {
+    fn default() -> Self {
+        Self {
+            compute_unit_limit: None,
+            compute_unit_price: None,
+            heap_frame_size: None,
+            commitment: CommitmentConfig::confirmed(),
+            skip_preflight: false,
+            max_retries: None,
+        }
+    }
+}
+
+impl SendOptions {
+    /// Returns the compute budget instructions to put in front of the transaction instructions.
+    pub fn compute_budget_instructions(&self) -> Vec<Instruction> {
+        let mut instructions = Vec::new();
+        if let Some(units) = self.compute_unit_limit {
+            instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(units));
+        }
+        if let Some(micro_lamports) = self.compute_unit_price {
+            instructions.push(ComputeBudgetInstruction::set_compute_unit_price(
+                micro_lamports,
+            ));
+        }
+        if let Some(bytes) = self.heap_frame_size {
+            instructions.push(ComputeBudgetInstruction::request_heap_frame(bytes));
+        }
+        instructions
+    }
+}
@@ -0,0 +1,120 @@
+use crate::{
+    backend::preflight_failure,
+    transaction_outcome::{decode_events, decode_return_data, parse_return_data},
+};
+use anchor_client::{
+    anchor_lang::{AccountDeserialize, Event},
+    solana_client::rpc_response::RpcSimulateTransactionResult,
+    solana_sdk::{
+        account::Account, pubkey::Pubkey, transaction::TransactionError,
+        transaction_context::TransactionReturnData,
+    },
+    ClientError as Error,
+};
+use borsh::BorshDeserialize;
+use fehler::{throw, throws};
+
+/// The outcome of a transaction simulated by [Client](crate::Client), nothing is committed.
+///
+/// Unlike [TransactionOutcome](crate::TransactionOutcome), the outcome is returned
+/// for failed transactions too, see [SimulationOutcome::error].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulationOutcome {
+    /// The error the transaction would fail with.
+    pub error: Option<TransactionError>,
+    /// The log messages emitted during the simulation.
+    pub logs: Vec<String>,
+    /// The compute units consumed by the transaction, if reported.
+    pub compute_units_consumed: Option<u64>,
+    /// The return data set by the last program that called `set_return_data`.
+    pub return_data: Option<TransactionReturnData>,
+    /// The requested accounts after the transaction, `None` for the accounts that would not exist.
+    ///
+    /// It is empty when the transaction failed.
+    pub accounts: Vec<(Pubkey, Option<Account>)>,
+}
+
+impl SimulationOutcome {
+    #[throws]
+    pub(crate) fn new(result: RpcSimulateTransactionResult, addresses: &[Pubkey]) -> Self {
+        let mut accounts = Vec::new();
+        for (address, account) in addresses.iter().zip(result.accounts.unwrap_or_default()) {
+            let account =
+                match account {
+                    Some(account) => Some(account.decode::<Account>().ok_or_else(|| {
+                        Error::LogParseError("Account decoding failed".to_string())
+                    })?),
+                    None => None,
+                };
+            accounts.push((*address, account));
+        }
+        Self {
+            error: result.err,
+            logs: result.logs.unwrap_or_default(),
+            compute_units_consumed: result.units_consumed,
+            return_data: parse_return_data(result.return_data)?,
+            accounts,
+        }
+    }
+
+    /// Returns `true` when the transaction would succeed.
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none()
+    }
+
+    /// Returns the outcome of the successful simulation, or the same error
+    /// [Client::send_transaction](crate::Client::send_transaction) would fail with.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let simulation = client.simulate_instruction(/* ... */).await?;
+    /// assert_anchor_error!(simulation.into_result(), escrow::EscrowError::Unauthorized);
+    /// ```
+    #[throws]
+    pub fn into_result(self) -> Self {
+        if let Some(error) = self.error {
+            throw!(preflight_failure(RpcSimulateTransactionResult {
+                err: Some(error),
+                logs: Some(self.logs),
+                accounts: None,
+                units_consumed: self.compute_units_consumed,
+                return_data: self.return_data.map(Into::into),
+            }));
+        }
+        self
+    }
+
+    /// Returns the requested account after the transaction, if it would exist.
+    pub fn account(&self, address: &Pubkey) -> Option<&Account> {
+        self.accounts
+            .iter()
+            .find(|(account_address, _)| account_address == address)
+            .and_then(|(_, account)| account.as_ref())
+    }
+
+    /// Deserializes the requested Anchor account after the transaction.
+    ///
+    /// # Errors
+    ///
+    /// It fails when the account was not requested, would not exist or cannot be deserialized.
+    #[throws]
+    pub fn account_data<T: AccountDeserialize>(&self, address: &Pubkey) -> T {
+        let account = self.account(address).ok_or(Error::AccountNotFound)?;
+        T::try_deserialize(&mut account.data.as_slice())?
+    }
+
+    /// Decodes the return data into the chosen type,
+    /// see [TransactionOutcome::return_data_as](crate::TransactionOutcome::return_data_as).
+    #[throws]
+    pub fn return_data_as<T: BorshDeserialize>(&self) -> Option<T> {
+        decode_return_data(self.return_data.as_ref())?
+    }
+
+    /// Decodes all events of the chosen type emitted with `emit!` during the simulation,
+    /// see [TransactionOutcome::events](crate::TransactionOutcome::events).
+    #[throws]
+    pub fn events<E: Event>(&self) -> Vec<E> {
+        decode_events(&self.logs)?
+    }
+}
@@ -1,4 +1,5 @@
 use crate::{commander::Error, Commander, LocalnetHandle};
+use anchor_client::solana_sdk::{account::Account, pubkey::Pubkey};
 use fehler::throws;
 use log::debug;
 use std::{borrow::Cow, mem};
@@ -9,17 +10,29 @@ use std::{borrow::Cow, mem};
 #[derive(Default)]
 pub struct Tester {
     root: Cow<'static, str>,
+    accounts: Vec<(Pubkey, Account)>,
 }
 
 impl Tester {
     pub fn new() -> Self {
         Self {
             root: "../../".into(),
+            accounts: Vec::new(),
         }
     }
 
     pub fn with_root(root: impl Into<Cow<'static, str>>) -> Self {
-        Self { root: root.into() }
+        Self {
+            root: root.into(),
+            accounts: Vec::new(),
+        }
+    }
+
+    /// Creates the `accounts` in the genesis of the localnet,
+    /// see [Commander::start_localnet_with_accounts].
+    pub fn with_accounts(mut self, accounts: impl IntoIterator<Item = (Pubkey, Account)>) -> Self {
+        self.accounts.extend(accounts);
+        self
     }
 
     #[throws]
@@ -27,7 +40,9 @@ impl Tester {
         debug!("_____________________");
         debug!("____ BEFORE TEST ____");
         let commander = Commander::with_root(mem::take(&mut self.root));
-        commander.start_localnet().await?
+        commander
+            .start_localnet_with_accounts(&mem::take(&mut self.accounts))
+            .await?
     }
 
     #[throws]
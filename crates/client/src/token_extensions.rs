@@ -0,0 +1,116 @@
+use anchor_client::solana_sdk::{
+    instruction::Instruction, program_error::ProgramError, pubkey::Pubkey,
+};
+use spl_token_2022::extension::{
+    interest_bearing_mint, metadata_pointer, transfer_fee, transfer_hook, ExtensionType,
+};
+
+/// The Token-2022 mint extension initialized by
+/// [Client::create_token_2022_mint](crate::Client::create_token_2022_mint).
+///
+/// _Note_: The Token-2022 program bundled with `ProgramTest` (`0.6`) does not support
+/// the [MintExtension::MetadataPointer] and [MintExtension::TransferHook] extensions yet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MintExtension {
+    /// The fee charged on every transfer, withheld in the recipient's token account.
+    TransferFee {
+        /// Can change the fee.
+        authority: Option<Pubkey>,
+        /// Can withdraw the withheld fees.
+        withdraw_withheld_authority: Option<Pubkey>,
+        /// The fee in hundredths of a percent of the transferred amount.
+        basis_points: u16,
+        /// The maximum fee of a transfer in tokens.
+        maximum_fee: u64,
+    },
+    /// The interest accrued continuously; only the UI amount changes.
+    InterestBearing {
+        rate_authority: Option<Pubkey>,
+        /// The interest rate in basis points.
+        rate: i16,
+    },
+    /// The tokens cannot be transferred, they can be burned only.
+    NonTransferable,
+    /// The delegate of all token accounts of the mint.
+    PermanentDelegate { delegate: Pubkey },
+    /// The address of the account with the token metadata.
+    MetadataPointer {
+        authority: Option<Pubkey>,
+        metadata_address: Option<Pubkey>,
+    },
+    /// The program invoked on every transfer.
+    TransferHook {
+        authority: Option<Pubkey>,
+        program_id: Option<Pubkey>,
+    },
+}
+
+impl MintExtension {
+    /// Returns the type of the extension.
+    pub fn extension_type(&self) -> ExtensionType {
+        match self {
+            Self::TransferFee { .. } => ExtensionType::TransferFeeConfig,
+            Self::InterestBearing { .. } => ExtensionType::InterestBearingConfig,
+            Self::NonTransferable => ExtensionType::NonTransferable,
+            Self::PermanentDelegate { .. } => ExtensionType::PermanentDelegate,
+            Self::MetadataPointer { .. } => ExtensionType::MetadataPointer,
+            Self::TransferHook { .. } => ExtensionType::TransferHook,
+        }
+    }
+
+    /// Returns the instruction initializing the extension.
+    ///
+    /// It has to be processed before the mint is initialized.
+    pub fn initialize_instruction(&self, mint: &Pubkey) -> Result<Instruction, ProgramError> {
+        let token_program = &spl_token_2022::ID;
+        match self {
+            Self::TransferFee {
+                authority,
+                withdraw_withheld_authority,
+                basis_points,
+                maximum_fee,
+            } => transfer_fee::instruction::initialize_transfer_fee_config(
+                token_program,
+                mint,
+                authority.as_ref(),
+                withdraw_withheld_authority.as_ref(),
+                *basis_points,
+                *maximum_fee,
+            ),
+            Self::InterestBearing {
+                rate_authority,
+                rate,
+            } => interest_bearing_mint::instruction::initialize(
+                token_program,
+                mint,
+                *rate_authority,
+                *rate,
+            ),
+            Self::NonTransferable => {
+                spl_token_2022::instruction::initialize_non_transferable_mint(token_program, mint)
+            }
+            Self::PermanentDelegate { delegate } => {
+                spl_token_2022::instruction::initialize_permanent_delegate(
+                    token_program,
+                    mint,
+                    delegate,
+                )
+            }
+            Self::MetadataPointer {
+                authority,
+                metadata_address,
+            } => metadata_pointer::instruction::initialize(
+                token_program,
+                mint,
+                *authority,
+                *metadata_address,
+            ),
+            Self::TransferHook {
+                authority,
+                program_id,
+            } => {
+                transfer_hook::instruction::initialize(token_program, mint, *authority, *program_id)
+            }
+        }
+    }
+}
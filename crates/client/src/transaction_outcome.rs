@@ -0,0 +1,316 @@
+use crate::PrintableTransaction;
+use anchor_client::{
+    anchor_lang::Event,
+    solana_sdk::{
+        clock::Slot, pubkey::Pubkey, signature::Signature,
+        transaction_context::TransactionReturnData,
+    },
+    ClientError as Error,
+};
+use borsh::BorshDeserialize;
+use fehler::{throw, throws};
+use solana_transaction_status::{
+    EncodedConfirmedTransactionWithStatusMeta, UiInstruction, UiLoadedAddresses,
+    UiReturnDataEncoding, UiTransactionReturnData,
+};
+use std::str::FromStr;
+
+const PROGRAM_DATA_LOG_PREFIX: &str = "Program data: ";
+
+/// The outcome of a confirmed transaction sent by [Client](crate::Client).
+///
+/// The raw transaction is kept in [TransactionOutcome::transaction],
+/// the other fields are decoded from it for convenience.
+#[derive(Debug)]
+pub struct TransactionOutcome {
+    /// The first signature of the transaction.
+    pub signature: Signature,
+    /// The slot the transaction was processed in.
+    pub slot: Slot,
+    /// The fee paid for the transaction in lamports.
+    pub fee: u64,
+    /// The log messages emitted during the transaction processing.
+    pub logs: Vec<String>,
+    /// The compute units consumed by the transaction, if reported.
+    pub compute_units_consumed: Option<u64>,
+    /// The top-level instructions with their inner (CPI) instructions.
+    ///
+    /// _Note_: The `ProgramTest` backend does not record inner instructions.
+    pub instructions: Vec<InstructionNode>,
+    /// The return data set by the last program that called `set_return_data`.
+    pub return_data: Option<TransactionReturnData>,
+    /// The confirmed transaction as returned by the backend.
+    pub transaction: EncodedConfirmedTransactionWithStatusMeta,
+}
+
+/// An instruction invoked in a transaction together with the instructions it invoked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstructionNode {
+    pub program_id: Pubkey,
+    pub accounts: Vec<Pubkey>,
+    pub data: Vec<u8>,
+    /// The invocation depth; top-level instructions have the stack height `1`.
+    pub stack_height: u32,
+    pub inner_instructions: Vec<InstructionNode>,
+}
+
+impl TransactionOutcome {
+    /// Decodes the return data into the chosen type.
+    ///
+    /// Returns `None` when the transaction did not set any return data.
+    ///
+    /// # Errors
+    ///
+    /// It fails when the return data cannot be deserialized with Borsh,
+    /// i.e. the way Anchor serializes values returned from instructions.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let outcome = my_program_instruction::get_price(&client, a_oracle, []).await?;
+    /// let price: Option<u64> = outcome.return_data_as::<u64>()?;
+    /// ```
+    #[throws]
+    pub fn return_data_as<T: BorshDeserialize>(&self) -> Option<T> {
+        decode_return_data(self.return_data.as_ref())?
+    }
+
+    /// Decodes all events of the chosen type emitted with `emit!` during the transaction.
+    ///
+    /// The `Program data:` log lines are matched by the 8-byte event discriminator
+    /// and the rest of the data is deserialized with Borsh.
+    ///
+    /// # Errors
+    ///
+    /// It fails when the data of a matching log line cannot be decoded.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let outcome = escrow_instruction::exchange(&client, /* ... */).await?;
+    /// let exchanged = outcome.events::<escrow::Exchanged>()?;
+    /// assert_eq!(exchanged[0].amount, 500);
+    /// ```
+    #[throws]
+    pub fn events<E: Event>(&self) -> Vec<E> {
+        decode_events(&self.logs)?
+    }
+
+    /// Iterates over all invoked instructions, the inner ones right after their parent.
+    pub fn all_instructions(&self) -> impl Iterator<Item = &InstructionNode> {
+        fn flatten(nodes: &[InstructionNode]) -> Vec<&InstructionNode> {
+            nodes
+                .iter()
+                .flat_map(|node| {
+                    std::iter::once(node).chain(flatten(&node.inner_instructions).into_iter())
+                })
+                .collect()
+        }
+        flatten(&self.instructions).into_iter()
+    }
+}
+
+impl TryFrom<EncodedConfirmedTransactionWithStatusMeta> for TransactionOutcome {
+    type Error = Error;
+
+    #[throws]
+    fn try_from(transaction: EncodedConfirmedTransactionWithStatusMeta) -> Self {
+        let decoded = transaction
+            .transaction
+            .transaction
+            .decode()
+            .ok_or_else(|| Error::LogParseError("Transaction decoding failed".to_string()))?;
+        let meta = transaction
+            .transaction
+            .meta
+            .clone()
+            .ok_or_else(|| Error::LogParseError("Transaction meta is missing".to_string()))?;
+
+        let mut account_keys = decoded.message.static_account_keys().to_vec();
+        if let Some(UiLoadedAddresses { writable, readonly }) =
+            Option::from(meta.loaded_addresses.clone())
+        {
+            for address in writable.iter().chain(readonly.iter()) {
+                account_keys.push(parse_pubkey(address)?);
+            }
+        }
+        let account_key = |index: u8| {
+            account_keys
+                .get(index as usize)
+                .copied()
+                .ok_or_else(|| Error::LogParseError("Invalid account index".to_string()))
+        };
+
+        let mut instructions = decoded
+            .message
+            .instructions()
+            .iter()
+            .map(|instruction| {
+                Ok(InstructionNode {
+                    program_id: account_key(instruction.program_id_index)?,
+                    accounts: instruction
+                        .accounts
+                        .iter()
+                        .map(|index| account_key(*index))
+                        .collect::<Result<_, Error>>()?,
+                    data: instruction.data.clone(),
+                    stack_height: 1,
+                    inner_instructions: Vec::new(),
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let inner_instructions: Option<Vec<_>> = meta.inner_instructions.into();
+        for inner in inner_instructions.unwrap_or_default() {
+            let parent = instructions
+                .get_mut(inner.index as usize)
+                .ok_or_else(|| Error::LogParseError("Invalid instruction index".to_string()))?;
+            for instruction in inner.instructions {
+                let instruction = match instruction {
+                    UiInstruction::Compiled(instruction) => instruction,
+                    UiInstruction::Parsed(_) => {
+                        throw!(Error::LogParseError(
+                            "Parsed inner instructions are not supported".to_string()
+                        ))
+                    }
+                };
+                let node = InstructionNode {
+                    program_id: account_key(instruction.program_id_index)?,
+                    accounts: instruction
+                        .accounts
+                        .iter()
+                        .map(|index| account_key(*index))
+                        .collect::<Result<_, Error>>()?,
+                    data: bs58::decode(&instruction.data).into_vec().map_err(|_| {
+                        Error::LogParseError("Instruction data decoding failed".to_string())
+                    })?,
+                    // nodes without the recorded stack height are handled as direct CPIs
+                    stack_height: instruction.stack_height.unwrap_or(2),
+                    inner_instructions: Vec::new(),
+                };
+                insert_instruction_node(parent, node);
+            }
+        }
+
+        let return_data = parse_return_data(meta.return_data.into())?;
+
+        Self {
+            signature: decoded.signatures[0],
+            slot: transaction.slot,
+            fee: meta.fee,
+            logs: Option::from(meta.log_messages).unwrap_or_default(),
+            compute_units_consumed: meta.compute_units_consumed.into(),
+            instructions,
+            return_data,
+            transaction,
+        }
+    }
+}
+
+impl PrintableTransaction for TransactionOutcome {
+    fn print_named(&self, name: &str) {
+        self.transaction.print_named(name);
+    }
+}
+
+/// Decodes the return data with Borsh.
+#[throws]
+pub(crate) fn decode_return_data<T: BorshDeserialize>(
+    return_data: Option<&TransactionReturnData>,
+) -> Option<T> {
+    match return_data {
+        Some(return_data) => {
+            Some(T::try_from_slice(&return_data.data).map_err(|_| {
+                Error::LogParseError("Return data deserialization failed".to_string())
+            })?)
+        }
+        None => None,
+    }
+}
+
+/// Decodes the events of the chosen type from the `Program data:` log lines.
+#[throws]
+pub(crate) fn decode_events<E: Event>(logs: &[String]) -> Vec<E> {
+    let mut events = Vec::new();
+    for log in logs {
+        let encoded = match log.strip_prefix(PROGRAM_DATA_LOG_PREFIX) {
+            Some(encoded) => encoded,
+            None => continue,
+        };
+        // `sol_log_data` logs every data slice, Anchor emits the event as the only one
+        let encoded = encoded.split(' ').next().unwrap_or_default();
+        let data = match base64::decode(encoded) {
+            Ok(data) => data,
+            Err(_) => continue,
+        };
+        if data.len() < 8 || data[..8] != E::DISCRIMINATOR {
+            continue;
+        }
+        events.push(
+            E::try_from_slice(&data[8..])
+                .map_err(|_| Error::LogParseError("Event deserialization failed".to_string()))?,
+        );
+    }
+    events
+}
+
+/// Inserts the node as the last child of the deepest last node above its stack height.
+fn insert_instruction_node(parent: &mut InstructionNode, node: InstructionNode) {
+    match parent.inner_instructions.last_mut() {
+        Some(last) if last.stack_height < node.stack_height => insert_instruction_node(last, node),
+        _ => parent.inner_instructions.push(node),
+    }
+}
+
+#[throws]
+pub(crate) fn parse_return_data(
+    return_data: Option<UiTransactionReturnData>,
+) -> Option<TransactionReturnData> {
+    match return_data {
+        Some(UiTransactionReturnData {
+            program_id,
+            data: (data, UiReturnDataEncoding::Base64),
+        }) => Some(TransactionReturnData {
+            program_id: parse_pubkey(&program_id)?,
+            data: base64::decode(data)
+                .map_err(|_| Error::LogParseError("Return data decoding failed".to_string()))?,
+        }),
+        None => None,
+    }
+}
+
+#[throws(Error)]
+pub(crate) fn parse_pubkey(pubkey: &str) -> Pubkey {
+    Pubkey::from_str(pubkey).map_err(|_| Error::LogParseError("Invalid pubkey".to_string()))?
+}
+
+/// Asserts that the transaction emitted an Anchor event matching the given struct pattern.
+///
+/// The pattern can be followed by an `if` guard. The event does not need to implement `PartialEq`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let outcome = escrow_instruction::exchange(&client, /* ... */).await?;
+/// assert_event_emitted!(outcome, escrow::Exchanged { amount: 500, .. });
+/// assert_event_emitted!(outcome, escrow::Exchanged { taker, .. } if taker == bob.pubkey());
+/// ```
+#[macro_export]
+macro_rules! assert_event_emitted {
+    ($outcome:expr, $($event:ident)::+ { $($fields:tt)* } $(if $guard:expr)? $(,)?) => {{
+        let events = $outcome
+            .events::<$($event)::+>()
+            .expect("events decoding failed");
+        assert!(
+            events.iter().any(|event| matches!(
+                event,
+                $($event)::+ { $($fields)* } $(if $guard)?
+            )),
+            "no `{}` event matching `{}` was emitted ({} `{}` event(s) found)",
+            stringify!($($event)::+),
+            stringify!($($event)::+ { $($fields)* } $(if $guard)?),
+            events.len(),
+            stringify!($($event)::+),
+        );
+    }};
+}
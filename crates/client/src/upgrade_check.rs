@@ -0,0 +1,99 @@
+use anchor_client::{
+    anchor_lang::{AccountDeserialize, Discriminator},
+    solana_sdk::pubkey::Pubkey,
+};
+use std::{any::type_name, fmt};
+
+type Decoder = fn(&[u8]) -> Result<(), String>;
+
+/// The Anchor account types of the program checked by [Client::check_upgrade](crate::Client::check_upgrade).
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let account_types = AccountTypes::new()
+///     .with::<escrow::EscrowAccount>()
+///     .with::<escrow::Config>();
+/// ```
+#[derive(Default)]
+pub struct AccountTypes {
+    types: Vec<(&'static str, [u8; 8], Decoder)>,
+}
+
+impl AccountTypes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the account type, its accounts are recognized by the discriminator.
+    pub fn with<T: AccountDeserialize + Discriminator>(mut self) -> Self {
+        self.types
+            .push((type_name::<T>(), T::DISCRIMINATOR, |mut data| {
+                T::try_deserialize(&mut data)
+                    .map(|_| ())
+                    .map_err(|error| error.to_string())
+            }));
+        self
+    }
+
+    /// Finds the account type by the discriminator and tries to deserialize the data.
+    ///
+    /// Returns `None` when no registered type matches.
+    pub(crate) fn decode(&self, data: &[u8]) -> Option<(&'static str, Result<(), String>)> {
+        let discriminator = data.get(..8)?;
+        self.types
+            .iter()
+            .find(|(_, type_discriminator, _)| type_discriminator == discriminator)
+            .map(|(name, _, decode)| (*name, decode(data)))
+    }
+}
+
+/// The program account that cannot be deserialized after the upgrade.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountDecodeFailure {
+    pub address: Pubkey,
+    /// The name of the account type matched by the discriminator.
+    pub account_type: &'static str,
+    pub error: String,
+}
+
+/// The result of [Client::check_upgrade](crate::Client::check_upgrade).
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct UpgradeReport {
+    /// The accounts successfully deserialized after the upgrade with the names of their types.
+    pub decoded: Vec<(Pubkey, &'static str)>,
+    /// The accounts that failed to deserialize after the upgrade.
+    pub failures: Vec<AccountDecodeFailure>,
+    /// The program accounts without a discriminator of any registered type.
+    pub unknown: Vec<Pubkey>,
+}
+
+impl UpgradeReport {
+    /// Returns `true` when all program accounts of the registered types are deserializable.
+    pub fn is_ok(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+impl fmt::Display for UpgradeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} account(s) decoded, {} failed, {} of unknown type",
+            self.decoded.len(),
+            self.failures.len(),
+            self.unknown.len()
+        )?;
+        for failure in &self.failures {
+            writeln!(
+                f,
+                "  {} ({}): {}",
+                failure.address, failure.account_type, failure.error
+            )?;
+        }
+        for address in &self.unknown {
+            writeln!(f, "  {address}: unknown account type")?;
+        }
+        Ok(())
+    }
+}
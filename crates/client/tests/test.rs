@@ -35,3 +35,2127 @@ pub async fn generate_program_client() {
 
     assert_str_eq!(client_code, expected_client_code);
 }
+
+#[throws]
+#[tokio::test]
+pub async fn generate_pda_address_functions() {
+    use trdelnik_client::idl::{IdlPda, IdlSeed};
+
+    // A program with the `seeds` constraints, the escrow example has none.
+    let expanded_anchor_program = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/test_data/expanded_pda_program.rs"
+    ));
+
+    let expected_client_code = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/test_data/expected_pda_client_code.rs"
+    ));
+
+    let program_idl =
+        trdelnik_client::idl::parse_to_idl_program("vault".to_owned(), expanded_anchor_program)
+            .await?;
+    let (_, idl_account_group) = &program_idl.instruction_account_pairs[0];
+    assert_eq!(
+        idl_account_group.pdas,
+        vec![
+            IdlPda {
+                account: "vault".to_owned(),
+                seeds: vec![
+                    IdlSeed::Const("b\"vault\"".to_owned()),
+                    IdlSeed::Account("authority".to_owned()),
+                    IdlSeed::Bytes("pool_mint".to_owned()),
+                    IdlSeed::Argument {
+                        name: "name".to_owned(),
+                        ty: "String".to_owned(),
+                        expr: "name . as_bytes ()".to_owned(),
+                    },
+                ],
+                bump: Some("pool . bump".to_owned()),
+            },
+            IdlPda {
+                account: "config".to_owned(),
+                seeds: vec![IdlSeed::Const("crate :: CONFIG_SEED".to_owned())],
+                bump: None,
+            },
+        ]
+    );
+
+    let idl = trdelnik_client::idl::Idl {
+        programs: vec![program_idl],
+    };
+
+    let use_modules: Vec<syn::ItemUse> = vec![syn::parse_quote! { use trdelnik_client::*; }];
+    let client_code =
+        trdelnik_client::program_client_generator::generate_source_code(idl, &use_modules);
+    let client_code = trdelnik_client::Commander::format_program_code(&client_code).await?;
+
+    assert_str_eq!(client_code, expected_client_code);
+}
+
+#[throws]
+#[tokio::test]
+pub async fn program_test_backend() {
+    use trdelnik_client::*;
+
+    let mut client = Client::with_program_test(Keypair::new(), ProgramTest::default()).await;
+
+    let wallet = Keypair::new();
+    client.airdrop(wallet.pubkey(), 5_000_000_000).await?;
+    assert_eq!(client.get_balance(&wallet.pubkey()).await?, 5_000_000_000);
+
+    let mint = Keypair::new();
+    client
+        .create_token_mint(spl_token::ID, &mint, client.payer().pubkey(), None, 2)
+        .await?;
+    let token_account = client
+        .create_associated_token_account(spl_token::ID, &wallet, mint.pubkey())
+        .await?;
+    let outcome = client
+        .mint_tokens(
+            spl_token::ID,
+            mint.pubkey(),
+            client.payer(),
+            token_account,
+            1_250,
+        )
+        .await?;
+    assert!(outcome
+        .logs
+        .iter()
+        .any(|log| log == "Program log: Instruction: MintTo"));
+    assert!(outcome.compute_units_consumed.unwrap() > 0);
+    assert_eq!(outcome.instructions[0].program_id, spl_token::ID);
+
+    assert!(matches!(
+        client.account_data_borsh::<u64>(Pubkey::new_unique()).await,
+        Err(ClientError::AccountNotFound)
+    ));
+    let result = client
+        .mint_tokens(spl_token::ID, mint.pubkey(), &wallet, token_account, 1)
+        .await;
+    assert!(transaction_error(result.as_ref().unwrap_err()).is_some());
+    assert!(!transaction_logs(result.as_ref().unwrap_err())
+        .unwrap()
+        .is_empty());
+
+    let balance = client.get_token_balance(token_account).await?;
+    assert_eq!(balance.amount, "1250");
+    assert_eq!(balance.ui_amount, Some(12.5));
+}
+
+#[throws]
+#[tokio::test]
+pub async fn decode_anchor_events() {
+    use trdelnik_client::{
+        anchor_lang::{self, prelude::*, Event},
+        assert_event_emitted,
+        solana_sdk::system_instruction,
+        Client, Keypair, ProgramTest, Signer as _,
+    };
+
+    #[event]
+    pub struct Deposited {
+        pub amount: u64,
+        pub memo: String,
+    }
+
+    #[event]
+    pub struct Withdrawn {
+        pub amount: u64,
+    }
+
+    let client = Client::with_program_test(Keypair::new(), ProgramTest::default()).await;
+    let mut outcome = client
+        .send_transaction(
+            &[system_instruction::transfer(
+                &client.payer().pubkey(),
+                &Pubkey::new_unique(),
+                1_000_000,
+            )],
+            [],
+        )
+        .await?;
+    assert!(outcome.events::<Deposited>()?.is_empty());
+
+    for event in [
+        Deposited {
+            amount: 10,
+            memo: "first".to_owned(),
+        }
+        .data(),
+        Withdrawn { amount: 5 }.data(),
+        Deposited {
+            amount: 20,
+            memo: "second".to_owned(),
+        }
+        .data(),
+    ] {
+        outcome
+            .logs
+            .push(format!("Program data: {}", base64::encode(event)));
+    }
+
+    let deposited = client.events::<Deposited>(&outcome)?;
+    assert_eq!(deposited.len(), 2);
+    assert_eq!(deposited[1].amount, 20);
+    assert_eq!(outcome.events::<Withdrawn>()?.len(), 1);
+
+    assert_event_emitted!(outcome, Deposited { amount: 10, .. });
+    assert_event_emitted!(outcome, Deposited { memo, .. } if memo == "second");
+    assert_event_emitted!(outcome, Withdrawn { amount: 5 });
+}
+
+mod failing_program {
+    use trdelnik_client::anchor_lang::{
+        self, prelude::*, solana_program::entrypoint::ProgramResult,
+    };
+
+    #[error_code]
+    pub enum FailingProgramError {
+        #[msg("The signer is not authorized")]
+        Unauthorized,
+    }
+
+    pub fn process_instruction(
+        _program_id: &Pubkey,
+        _accounts: &[AccountInfo],
+        data: &[u8],
+    ) -> ProgramResult {
+        let error: anchor_lang::error::Error = match data {
+            [0] => FailingProgramError::Unauthorized.into(),
+            _ => return Err(ProgramError::Custom(ErrorCode::ConstraintMut.into())),
+        };
+        error.log();
+        Err(error.into())
+    }
+
+    /// Invokes the program of the first account with the instruction data.
+    pub fn process_cpi(
+        _program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        data: &[u8],
+    ) -> ProgramResult {
+        anchor_lang::solana_program::program::invoke(
+            &anchor_lang::solana_program::instruction::Instruction::new_with_bytes(
+                *accounts[0].key,
+                data,
+                vec![],
+            ),
+            accounts,
+        )
+    }
+}
+
+#[throws]
+#[tokio::test]
+pub async fn custom_program_errors() {
+    use failing_program::FailingProgramError;
+    use trdelnik_client::{
+        anchor_lang::error::ErrorCode,
+        assert_anchor_error, processor,
+        solana_sdk::{instruction::AccountMeta, system_instruction},
+        *,
+    };
+
+    let (program_id, caller_id) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mut program_test = ProgramTest::new(
+        "failing_program",
+        program_id,
+        processor!(failing_program::process_instruction),
+    );
+    program_test.add_program(
+        "caller_program",
+        caller_id,
+        processor!(failing_program::process_cpi),
+    );
+    let client = Client::with_program_test(Keypair::new(), program_test).await;
+    let transfer_ix =
+        system_instruction::transfer(&client.payer().pubkey(), &Pubkey::new_unique(), 1_000_000);
+
+    let result = client
+        .send_transaction(
+            &[
+                transfer_ix.clone(),
+                Instruction::new_with_bytes(program_id, &[0], vec![]),
+            ],
+            [],
+        )
+        .await;
+    assert_anchor_error!(result, FailingProgramError::Unauthorized);
+    let error = custom_program_error(result.as_ref().unwrap_err()).unwrap();
+    assert_eq!(error.instruction_index, 1);
+    assert_eq!(error.program_id, Some(program_id));
+    assert_eq!(error.name.as_deref(), Some("Unauthorized"));
+    assert_eq!(
+        error.message.as_deref(),
+        Some("The signer is not authorized")
+    );
+
+    let result = client
+        .send_transaction(&[Instruction::new_with_bytes(program_id, &[1], vec![])], [])
+        .await
+        .map_err(Error::from);
+    assert_anchor_error!(result, ErrorCode::ConstraintMut);
+    let error = custom_program_error(result.as_ref().unwrap_err()).unwrap();
+    assert_eq!(error.instruction_index, 0);
+    assert_eq!(error.name.as_deref(), Some("ConstraintMut"));
+    assert_eq!(
+        error.message.as_deref(),
+        Some("A mut constraint was violated")
+    );
+
+    // the error raised by the invoked program
+    let result = client
+        .send_transaction(
+            &[Instruction::new_with_bytes(
+                caller_id,
+                &[0],
+                vec![AccountMeta::new_readonly(program_id, false)],
+            )],
+            [],
+        )
+        .await;
+    assert_anchor_error!(result, FailingProgramError::Unauthorized);
+    let error = custom_program_error(result.as_ref().unwrap_err()).unwrap();
+    assert_eq!(error.instruction_index, 0);
+    assert_eq!(error.program_id, Some(program_id));
+    assert_eq!(error.name.as_deref(), Some("Unauthorized"));
+}
+
+#[throws]
+#[tokio::test]
+pub async fn deploy_with_deploy_cost() {
+    use std::str::FromStr;
+    use trdelnik_client::{solana_sdk::account::Account, *};
+
+    // the loader transactions ignore the compute budget of the client,
+    // its priority fee (0.02 SOL per transaction) is not paid
+    let client = Client::with_program_test(Keypair::new(), ProgramTest::default())
+        .await
+        .with_send_options(SendOptions {
+            compute_unit_limit: Some(200_000),
+            compute_unit_price: Some(100_000_000),
+            ..SendOptions::default()
+        });
+
+    let memo_program_id = Pubkey::from_str("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr")?;
+    let program_data = client.get_account(memo_program_id).await?.unwrap().data;
+    // the same max data length as `deploy_by_name`
+    let max_data_len = program_data.len() * 2;
+    let deploy_cost = client.deploy_cost(program_data.len(), max_data_len).await?;
+
+    // the payer has just the deploy cost
+    client
+        .set_account(
+            client.payer().pubkey(),
+            Account {
+                lamports: deploy_cost,
+                owner: System::id(),
+                ..Account::default()
+            },
+        )
+        .await?;
+    // the upgrade authority signs the buffer writes besides the payer
+    let (program, authority) = (Keypair::new(), Keypair::new());
+    client
+        .deploy(&program, &program_data, &authority, max_data_len)
+        .await?;
+
+    // the upgrade writes a new buffer
+    client
+        .airdrop(client.payer().pubkey(), 1_000_000_000)
+        .await?;
+    client
+        .upgrade_program(program.pubkey(), &program_data, &authority)
+        .await?;
+    client
+        .set_upgrade_authority(program.pubkey(), &authority, Some(authority.pubkey()))
+        .await?;
+    client
+        .close_program(program.pubkey(), &authority, client.payer().pubkey())
+        .await?;
+}
+
+#[throws]
+#[tokio::test]
+pub async fn deploy_upgradeable_program() {
+    use std::str::FromStr;
+    use trdelnik_client::{
+        solana_sdk::{bpf_loader_upgradeable, instruction::Instruction},
+        *,
+    };
+
+    let client = Client::with_program_test(Keypair::new(), ProgramTest::default()).await;
+
+    // reuse the SPL Memo program binary bundled with `ProgramTest`
+    let memo_program_id = Pubkey::from_str("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr")?;
+    let program_data = client.get_account(memo_program_id).await?.unwrap().data;
+    let memo = |program_id| Instruction {
+        program_id,
+        accounts: vec![],
+        data: b"trdelnik".to_vec(),
+    };
+
+    let program = Keypair::new();
+    let authority = Keypair::new();
+    client
+        .deploy(&program, &program_data, &authority, program_data.len())
+        .await?;
+    let program_account = client.get_account(program.pubkey()).await?.unwrap();
+    assert_eq!(program_account.owner, bpf_loader_upgradeable::id());
+    assert!(program_account.executable);
+
+    let outcome = client
+        .send_transaction(&[memo(program.pubkey())], [])
+        .await?;
+    assert!(outcome
+        .logs
+        .iter()
+        .any(|log| log == "Program log: Memo (len 8): \"trdelnik\""));
+
+    assert!(client
+        .upgrade_program(program.pubkey(), &program_data, &Keypair::new())
+        .await
+        .is_err());
+    client
+        .upgrade_program(program.pubkey(), &program_data, &authority)
+        .await?;
+    client
+        .send_transaction(&[memo(program.pubkey())], [])
+        .await?;
+
+    let new_authority = Keypair::new();
+    client
+        .set_upgrade_authority(program.pubkey(), &authority, Some(new_authority.pubkey()))
+        .await?;
+    client
+        .close_program(program.pubkey(), &new_authority, client.payer().pubkey())
+        .await?;
+    let (program_data_address, _) =
+        Pubkey::find_program_address(&[program.pubkey().as_ref()], &bpf_loader_upgradeable::id());
+    assert!(client.get_account(program_data_address).await?.is_none());
+}
+
+mod zeroed_account {
+    use trdelnik_client::anchor_lang::{
+        self, prelude::*, AccountDeserialize, AnchorDeserialize, Discriminator,
+    };
+
+    /// An account type matching zero-filled accounts.
+    pub struct Zeroed(pub u64);
+
+    impl Discriminator for Zeroed {
+        const DISCRIMINATOR: [u8; 8] = [0; 8];
+    }
+
+    impl AccountDeserialize for Zeroed {
+        fn try_deserialize_unchecked(buf: &mut &[u8]) -> anchor_lang::Result<Self> {
+            let value = u64::deserialize(&mut &buf[8..])
+                .map_err(|_| ErrorCode::AccountDidNotDeserialize)?;
+            Ok(Self(value))
+        }
+    }
+}
+
+#[throws]
+#[tokio::test]
+pub async fn check_program_upgrade() {
+    use std::str::FromStr;
+    use trdelnik_client::*;
+    use zeroed_account::Zeroed;
+
+    let client = Client::with_program_test(Keypair::new(), ProgramTest::default()).await;
+
+    let memo_program_id = Pubkey::from_str("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr")?;
+    let program_data = client.get_account(memo_program_id).await?.unwrap().data;
+
+    let program = Keypair::new();
+    let (valid, invalid, unknown) = (Keypair::new(), Keypair::new(), Keypair::new());
+    let report = client
+        .check_upgrade(
+            &program,
+            &program_data,
+            &program_data,
+            &AccountTypes::new().with::<Zeroed>(),
+            async {
+                for (account, space) in [(&valid, 16), (&invalid, 12), (&unknown, 4)] {
+                    client
+                        .create_account(account, 1_000_000_000, space, &program.pubkey())
+                        .await?;
+                }
+                Ok(())
+            },
+        )
+        .await?;
+
+    assert!(!report.is_ok());
+    assert_eq!(
+        report.decoded,
+        vec![(valid.pubkey(), std::any::type_name::<Zeroed>())]
+    );
+    assert_eq!(report.failures.len(), 1);
+    assert_eq!(report.failures[0].address, invalid.pubkey());
+    assert_eq!(report.unknown, vec![unknown.pubkey()]);
+}
+
+mod counter_program {
+    use trdelnik_client::anchor_lang::solana_program::{
+        account_info::AccountInfo, entrypoint::ProgramResult, msg, pubkey::Pubkey,
+    };
+
+    /// Stores the instruction data byte into the first byte of the account.
+    pub fn process_v1(_: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+        msg!("set {}", data[0]);
+        accounts[0].data.borrow_mut()[0] = data[0];
+        Ok(())
+    }
+
+    /// Like `process_v1`, but it stores the double of values greater than 1.
+    pub fn process_v2(_: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+        msg!("set {}", data[0]);
+        accounts[0].data.borrow_mut()[0] = if data[0] > 1 { data[0] * 2 } else { data[0] };
+        Ok(())
+    }
+}
+
+#[throws]
+#[tokio::test]
+pub async fn differential_test() {
+    use trdelnik_client::{
+        solana_sdk::{instruction::AccountMeta, system_instruction},
+        *,
+    };
+
+    let program_id = Pubkey::new_unique();
+    let payer = Keypair::new();
+    let counter = Keypair::new();
+    let mut test = DifferentialTest::new(
+        payer.clone(),
+        ProgramTest::new(
+            "counter",
+            program_id,
+            processor!(counter_program::process_v1),
+        ),
+        ProgramTest::new(
+            "counter",
+            program_id,
+            processor!(counter_program::process_v2),
+        ),
+    )
+    .await;
+    let set = |value: u8| Instruction {
+        program_id,
+        accounts: vec![AccountMeta::new(counter.pubkey(), false)],
+        data: vec![value],
+    };
+
+    test.step(
+        "create counter",
+        &[system_instruction::create_account(
+            &payer.pubkey(),
+            &counter.pubkey(),
+            1_000_000_000,
+            1,
+            &program_id,
+        )],
+        &[&counter],
+    )
+    .await?;
+    test.step("set 1", &[set(1)], &[]).await?;
+    assert!(test.divergence().is_none());
+
+    test.step("set 3", &[set(3)], &[]).await?;
+    test.step("set 4", &[set(4)], &[]).await?;
+    let divergence = test.divergence().unwrap();
+    assert_eq!((divergence.step.as_str(), divergence.index), ("set 3", 2));
+    assert!(matches!(
+        &divergence.kind,
+        DivergenceKind::Account { address, .. } if *address == counter.pubkey()
+    ));
+    assert!(divergence
+        .to_string()
+        .contains("data differs from the byte 0 (showing from 0):\n- 03\n+ 06\n"));
+}
+
+#[throws]
+#[tokio::test]
+pub async fn program_accounts() {
+    use trdelnik_client::{
+        anchor_client::solana_client::rpc_filter::{Memcmp, RpcFilterType},
+        *,
+    };
+    use zeroed_account::Zeroed;
+
+    let client = Client::with_program_test(Keypair::new(), ProgramTest::default()).await;
+
+    let program_id = Pubkey::new_unique();
+    let accounts = [Keypair::new(), Keypair::new(), Keypair::new()];
+    for (account, space) in accounts.iter().zip([16, 16, 24]) {
+        client
+            .create_account(account, 1_000_000_000, space, &program_id)
+            .await?;
+    }
+
+    let mut found = client
+        .program_accounts::<Zeroed>(program_id, vec![RpcFilterType::DataSize(16)])
+        .await?
+        .into_iter()
+        .map(|(address, Zeroed(value))| (address, value))
+        .collect::<Vec<_>>();
+    found.sort();
+    let mut expected = vec![(accounts[0].pubkey(), 0), (accounts[1].pubkey(), 0)];
+    expected.sort();
+    assert_eq!(found, expected);
+
+    assert_eq!(
+        client
+            .program_accounts::<Zeroed>(program_id, Vec::new())
+            .await?
+            .len(),
+        3
+    );
+    assert!(client
+        .program_accounts::<Zeroed>(
+            program_id,
+            vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(8, vec![1]))],
+        )
+        .await?
+        .is_empty());
+}
+
+#[throws]
+#[tokio::test]
+pub async fn program_accounts_in_genesis() {
+    use trdelnik_client::{solana_sdk::account::Account, *};
+    use zeroed_account::Zeroed;
+
+    let program_id = Pubkey::new_unique();
+    let account = |data_len| Account {
+        lamports: 1_000_000_000,
+        data: vec![0; data_len],
+        owner: program_id,
+        ..Account::default()
+    };
+    let (added, genesis) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mut program_test = ProgramTest::default();
+    program_test.add_account(added, account(16));
+
+    let client = Client::with_program_test_and_accounts(
+        Keypair::new(),
+        program_test,
+        [(genesis, account(24))],
+    )
+    .await;
+
+    // `ProgramTest` cannot list its accounts, only the ones passed to the client are found
+    let found = client
+        .program_accounts::<Zeroed>(program_id, Vec::new())
+        .await?
+        .into_iter()
+        .map(|(address, _)| address)
+        .collect::<Vec<_>>();
+    assert_eq!(found, vec![genesis]);
+    assert!(client.get_account(added).await?.is_some());
+}
+
+// `#[account]` refers to the program id as `crate::ID`
+use order_book::ID;
+
+mod order_book {
+    use trdelnik_client::anchor_lang::{
+        self,
+        prelude::*,
+        solana_program::{entrypoint::ProgramResult, pubkey::Pubkey},
+    };
+
+    declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+    #[account(zero_copy)]
+    pub struct OrderBook {
+        pub bids: [u64; 4],
+        pub len: u64,
+    }
+
+    #[account]
+    #[derive(Debug, PartialEq)]
+    pub struct Market {
+        pub authority: Pubkey,
+        pub tick_size: u64,
+    }
+
+    #[account]
+    pub struct Pool {
+        pub name: String,
+        pub fees: Fees,
+        pub state: PoolState,
+        pub holders: Vec<Pubkey>,
+    }
+
+    #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+    pub struct Fees {
+        pub maker: u16,
+        pub taker: u16,
+    }
+
+    #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+    pub enum PoolState {
+        Open,
+        Closed { at: i64 },
+    }
+
+    /// Writes the instruction data to the beginning of the account.
+    pub fn process_write(_: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+        accounts[0].data.borrow_mut()[..data.len()].copy_from_slice(data);
+        Ok(())
+    }
+}
+
+#[throws]
+#[tokio::test]
+pub async fn zero_copy_account_data() {
+    use order_book::OrderBook;
+    use trdelnik_client::{
+        anchor_lang::{error::ErrorCode, Discriminator},
+        solana_sdk::instruction::AccountMeta,
+        *,
+    };
+
+    let client = Client::with_program_test(
+        Keypair::new(),
+        ProgramTest::new(
+            "order_book",
+            order_book::ID,
+            processor!(order_book::process_write),
+        ),
+    )
+    .await;
+
+    let account = Keypair::new();
+    client
+        .create_account(
+            &account,
+            1_000_000_000,
+            8 + std::mem::size_of::<OrderBook>() as u64,
+            &order_book::ID,
+        )
+        .await?;
+    let write = |data: Vec<u8>| Instruction {
+        program_id: order_book::ID,
+        accounts: vec![AccountMeta::new(account.pubkey(), false)],
+        data,
+    };
+
+    assert!(matches!(
+        client.account_data_zero_copy::<OrderBook>(account.pubkey()).await,
+        Err(ClientError::AnchorError(error)) if error == ErrorCode::AccountDiscriminatorMismatch.into()
+    ));
+
+    let mut data = OrderBook::DISCRIMINATOR.to_vec();
+    for value in [10u64, 20, 30, 0, 3] {
+        data.extend(value.to_le_bytes());
+    }
+    client.send_transaction(&[write(data)], []).await?;
+
+    let order_book = client
+        .account_data_zero_copy::<OrderBook>(account.pubkey())
+        .await?;
+    assert_eq!(order_book.bids, [10, 20, 30, 0]);
+    assert_eq!(order_book.len, 3);
+}
+
+#[throws]
+#[tokio::test]
+pub async fn account_and_logs_subscriptions() {
+    use std::time::Duration;
+    use tokio::time::timeout;
+    use trdelnik_client::{
+        futures::StreamExt,
+        solana_sdk::{instruction::AccountMeta, system_instruction},
+        *,
+    };
+
+    let program_id = Pubkey::new_unique();
+    let client = Client::with_program_test(
+        Keypair::new(),
+        ProgramTest::new(
+            "counter",
+            program_id,
+            processor!(counter_program::process_v1),
+        ),
+    )
+    .await;
+    let counter = Keypair::new();
+
+    let mut account_updates = client.subscribe_account(counter.pubkey()).await?;
+    let mut logs = client.subscribe_logs(program_id).await?;
+
+    client
+        .send_transaction(
+            &[system_instruction::create_account(
+                &client.payer().pubkey(),
+                &counter.pubkey(),
+                1_000_000_000,
+                1,
+                &program_id,
+            )],
+            [&counter],
+        )
+        .await?;
+    let account = timeout(Duration::from_secs(5), account_updates.next())
+        .await?
+        .unwrap();
+    assert_eq!((account.owner, account.data), (program_id, vec![0]));
+
+    client
+        .send_transaction(
+            &[Instruction {
+                program_id,
+                accounts: vec![AccountMeta::new(counter.pubkey(), false)],
+                data: vec![7],
+            }],
+            [],
+        )
+        .await?;
+    let account = timeout(Duration::from_secs(5), account_updates.next())
+        .await?
+        .unwrap();
+    assert_eq!(account.data, vec![7]);
+
+    // the account creation does not mention the program
+    let logs = timeout(Duration::from_secs(5), logs.next()).await?.unwrap();
+    assert!(logs.logs.contains(&"Program log: set 7".to_string()));
+}
+
+#[throws]
+#[tokio::test]
+pub async fn versioned_transaction_with_lookup_table() {
+    use trdelnik_client::{
+        solana_sdk::{instruction::AccountMeta, system_instruction},
+        *,
+    };
+
+    let program_id = Pubkey::new_unique();
+    let client = Client::with_program_test(
+        Keypair::new(),
+        ProgramTest::new(
+            "counter",
+            program_id,
+            processor!(counter_program::process_v1),
+        ),
+    )
+    .await;
+    let counter = Keypair::new();
+    client
+        .send_transaction(
+            &[system_instruction::create_account(
+                &client.payer().pubkey(),
+                &counter.pubkey(),
+                1_000_000_000,
+                1,
+                &program_id,
+            )],
+            [&counter],
+        )
+        .await?;
+
+    // too many accounts for a legacy transaction
+    let extra_accounts = (0..40).map(|_| Pubkey::new_unique()).collect::<Vec<_>>();
+    let lookup_table = client.create_lookup_table(client.payer()).await?;
+    for addresses in extra_accounts.chunks(20) {
+        client
+            .extend_lookup_table(lookup_table, client.payer(), addresses.to_vec())
+            .await?;
+    }
+    assert_eq!(
+        client
+            .get_address_lookup_table(lookup_table)
+            .await?
+            .addresses,
+        extra_accounts
+    );
+
+    let mut accounts = vec![AccountMeta::new(counter.pubkey(), false)];
+    accounts.extend(
+        extra_accounts
+            .iter()
+            .map(|address| AccountMeta::new_readonly(*address, false)),
+    );
+    let outcome = client
+        .send_versioned_transaction(
+            &[Instruction {
+                program_id,
+                accounts,
+                data: vec![9],
+            }],
+            [client.payer()],
+            &[lookup_table],
+        )
+        .await?;
+    assert_eq!(outcome.instructions[0].accounts.len(), 41);
+    assert_eq!(outcome.instructions[0].accounts[1..], extra_accounts);
+    assert!(outcome.fee > 0);
+    assert_eq!(
+        client.get_account(counter.pubkey()).await?.unwrap().data,
+        [9]
+    );
+
+    client
+        .deactivate_lookup_table(lookup_table, client.payer())
+        .await?;
+}
+
+#[throws]
+#[tokio::test]
+pub async fn send_options() {
+    use std::str::FromStr;
+    use trdelnik_client::{
+        solana_sdk::{
+            compute_budget, instruction::InstructionError, system_instruction,
+            transaction::TransactionError,
+        },
+        *,
+    };
+
+    let client = Client::with_program_test(Keypair::new(), ProgramTest::default())
+        .await
+        .with_send_options(SendOptions {
+            compute_unit_price: Some(1_000_000),
+            ..SendOptions::default()
+        });
+    let wallet = Keypair::new();
+    let transfer =
+        system_instruction::transfer(&client.payer().pubkey(), &wallet.pubkey(), 1_000_000);
+
+    let outcome = client
+        .send_transaction_with_options(
+            &[transfer.clone()],
+            [],
+            &SendOptions {
+                compute_unit_limit: Some(10_000),
+                ..client.send_options().clone()
+            },
+        )
+        .await?;
+    assert_eq!(outcome.instructions.len(), 3);
+    assert!(outcome.instructions[..2]
+        .iter()
+        .all(|instruction| instruction.program_id == compute_budget::id()));
+    // the signature fee and the priority fee of 10_000 units for 1 lamport each
+    assert_eq!(outcome.fee, 5_000 + 10_000);
+
+    // the bundled SPL Memo program consumes more than 100 units
+    let memo = Instruction {
+        program_id: Pubkey::from_str("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr")?,
+        accounts: Vec::new(),
+        data: b"trdelnik".to_vec(),
+    };
+    let result = client
+        .send_transaction_with_options(
+            &[memo],
+            [],
+            &SendOptions {
+                compute_unit_limit: Some(100),
+                ..SendOptions::default()
+            },
+        )
+        .await;
+    let error = result.unwrap_err();
+    assert!(error_reporter::transaction_logs(&error)
+        .unwrap()
+        .iter()
+        .any(|log| log.contains("exceeded CUs meter")));
+    assert!(matches!(
+        error_reporter::transaction_error(&error),
+        Some(TransactionError::InstructionError(
+            1,
+            InstructionError::ProgramFailedToComplete
+        ))
+    ));
+}
+
+#[throws]
+#[tokio::test]
+pub async fn compute_units_baseline() {
+    use trdelnik_client::*;
+
+    let mut baseline = ComputeUnitBaseline::new();
+    baseline.insert("test_happy_path", "escrow::InitializeEscrow", 10_000);
+    baseline.insert("test_happy_path", "escrow::Exchange", 20_000);
+    baseline.insert("test_cancel", "escrow::CancelEscrow", 8_000);
+
+    let path = std::env::temp_dir().join(format!("{}-{CU_BASELINE_FILE}", Pubkey::new_unique()));
+    baseline.save(&path)?;
+    assert_eq!(ComputeUnitBaseline::load(&path)?, baseline);
+    std::fs::remove_file(path)?;
+
+    let mut current = ComputeUnitBaseline::new();
+    current.insert("test_happy_path", "escrow::InitializeEscrow", 10_400);
+    current.insert("test_happy_path", "escrow::Exchange", 22_000);
+    // the highest consumption is kept
+    current.insert("test_happy_path", "escrow::Exchange", 21_000);
+    current.insert("test_fuzz", "escrow::CancelEscrow", 8_000);
+
+    let report = baseline.compare(&current, 5.0);
+    assert_eq!(report.changes.len(), 4);
+    assert_eq!(
+        report
+            .regressions()
+            .map(|change| change.instruction.as_str())
+            .collect::<Vec<_>>(),
+        ["escrow::Exchange"]
+    );
+    assert!(!report.is_ok());
+
+    assert_str_eq!(
+        report.to_string(),
+        "\
+test             instruction               baseline  current   change  status
+test_cancel      escrow::CancelEscrow          8000        -        -  removed
+test_fuzz        escrow::CancelEscrow             -     8000        -  new
+test_happy_path  escrow::Exchange             20000    22000  +10.00%  REGRESSION
+test_happy_path  escrow::InitializeEscrow     10000    10400   +4.00%  ok
+1 regression(s) over the 5% threshold"
+    );
+}
+
+#[throws]
+#[tokio::test]
+pub async fn simulate_transaction() {
+    use trdelnik_client::{
+        solana_sdk::{
+            instruction::{AccountMeta, InstructionError},
+            system_instruction,
+            transaction::TransactionError,
+        },
+        *,
+    };
+
+    let program_id = Pubkey::new_unique();
+    let mut client = Client::with_program_test(
+        Keypair::new(),
+        ProgramTest::new(
+            "counter",
+            program_id,
+            processor!(counter_program::process_v1),
+        ),
+    )
+    .await;
+    let counter = Keypair::new();
+    client
+        .send_transaction(
+            &[system_instruction::create_account(
+                &client.payer().pubkey(),
+                &counter.pubkey(),
+                1_000_000_000,
+                1,
+                &program_id,
+            )],
+            [&counter],
+        )
+        .await?;
+    let payer_balance = client.get_balance(&client.payer().pubkey()).await?;
+    let slot = client.get_clock().await?.slot;
+
+    let set = Instruction {
+        program_id,
+        accounts: vec![AccountMeta::new(counter.pubkey(), false)],
+        data: vec![5],
+    };
+    let simulation = client.simulate_transaction(&[set.clone()], [], &[]).await?;
+    assert!(simulation.is_ok());
+    assert!(simulation.logs.contains(&"Program log: set 5".to_string()));
+    assert!(simulation.compute_units_consumed.is_some());
+    // `BanksClient` does not return the accounts after the simulation
+    assert!(client
+        .simulate_transaction(&[set.clone()], [], &[counter.pubkey()])
+        .await
+        .is_err());
+    // nothing is committed
+    assert_eq!(client.get_clock().await?.slot, slot);
+    assert_eq!(
+        client.get_account(counter.pubkey()).await?.unwrap().data,
+        [0]
+    );
+    assert_eq!(
+        client.get_balance(&client.payer().pubkey()).await?,
+        payer_balance
+    );
+
+    // the simulated transaction can be sent, it was not processed
+    client.send_transaction(&[set], []).await?;
+    assert_eq!(
+        client.get_account(counter.pubkey()).await?.unwrap().data,
+        [5]
+    );
+
+    let wallet = Keypair::new();
+    let simulation = client
+        .simulate_transaction(
+            &[system_instruction::transfer(
+                &client.payer().pubkey(),
+                &wallet.pubkey(),
+                u64::MAX,
+            )],
+            [],
+            &[],
+        )
+        .await?;
+    assert_eq!(
+        simulation.error,
+        Some(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(1)
+        ))
+    );
+    assert!(simulation.accounts.is_empty());
+    assert!(error_reporter::transaction_error(&simulation.into_result().unwrap_err()).is_some());
+    assert_eq!(client.get_account(wallet.pubkey()).await?, None);
+}
+
+#[throws]
+#[tokio::test]
+pub async fn token_helpers() {
+    use trdelnik_client::{
+        spl_token::{native_mint, state::AccountState},
+        spl_token_2022::instruction::AuthorityType,
+        *,
+    };
+
+    let mut client = Client::with_program_test(Keypair::new(), ProgramTest::default()).await;
+    let authority = Keypair::new();
+    let alice = Keypair::new();
+    let bob = Keypair::new();
+    let delegate = Keypair::new();
+    client.airdrop(alice.pubkey(), 1_000_000_000).await?;
+
+    let mint = Keypair::new();
+    client
+        .create_token_mint(
+            spl_token::ID,
+            &mint,
+            authority.pubkey(),
+            Some(authority.pubkey()),
+            2,
+        )
+        .await?;
+    let alice_tokens = client
+        .create_associated_token_account(spl_token::ID, &alice, mint.pubkey())
+        .await?;
+    let bob_tokens = client
+        .create_associated_token_account(spl_token::ID, &bob, mint.pubkey())
+        .await?;
+    client
+        .mint_tokens(
+            spl_token::ID,
+            mint.pubkey(),
+            &authority,
+            alice_tokens,
+            1_000,
+        )
+        .await?;
+
+    client
+        .transfer_tokens_checked(
+            spl_token::ID,
+            alice_tokens,
+            mint.pubkey(),
+            bob_tokens,
+            &alice,
+            300,
+            2,
+        )
+        .await?;
+    assert_eq!(client.get_token_account(bob_tokens).await?.amount, 300);
+
+    client
+        .approve_delegate(spl_token::ID, alice_tokens, delegate.pubkey(), &alice, 200)
+        .await?;
+    client
+        .burn_tokens(spl_token::ID, alice_tokens, mint.pubkey(), &delegate, 150)
+        .await?;
+    let alice_account = client.get_token_account(alice_tokens).await?;
+    assert_eq!(alice_account.amount, 550);
+    assert_eq!(alice_account.delegated_amount, 50);
+    client
+        .revoke_delegate(spl_token::ID, alice_tokens, &alice)
+        .await?;
+    assert!(client
+        .get_token_account(alice_tokens)
+        .await?
+        .delegate
+        .is_none());
+    assert_eq!(client.get_mint(mint.pubkey()).await?.supply, 850);
+
+    client
+        .freeze_token_account(spl_token::ID, bob_tokens, mint.pubkey(), &authority)
+        .await?;
+    assert_eq!(
+        client.get_token_account(bob_tokens).await?.state,
+        AccountState::Frozen
+    );
+    assert!(client
+        .transfer_tokens_checked(
+            spl_token::ID,
+            bob_tokens,
+            mint.pubkey(),
+            alice_tokens,
+            &bob,
+            1,
+            2
+        )
+        .await
+        .is_err());
+    client
+        .thaw_token_account(spl_token::ID, bob_tokens, mint.pubkey(), &authority)
+        .await?;
+    client
+        .transfer_tokens_checked(
+            spl_token::ID,
+            bob_tokens,
+            mint.pubkey(),
+            alice_tokens,
+            &bob,
+            300,
+            2,
+        )
+        .await?;
+
+    client
+        .set_token_authority(
+            spl_token::ID,
+            mint.pubkey(),
+            AuthorityType::MintTokens,
+            &authority,
+            None,
+        )
+        .await?;
+    assert!(client
+        .get_mint(mint.pubkey())
+        .await?
+        .mint_authority
+        .is_none());
+
+    let bob_balance = client.get_balance(&bob.pubkey()).await?;
+    client
+        .close_token_account(spl_token::ID, bob_tokens, bob.pubkey(), &bob)
+        .await?;
+    assert_eq!(client.get_account(bob_tokens).await?, None);
+    assert!(client.get_balance(&bob.pubkey()).await? > bob_balance);
+
+    let wrapped_sol = client
+        .create_wrapped_sol_account(spl_token::ID, alice.pubkey(), 500_000)
+        .await?;
+    let wrapped_sol_account = client.get_token_account(wrapped_sol).await?;
+    assert_eq!(wrapped_sol_account.mint, native_mint::ID);
+    assert_eq!(wrapped_sol_account.amount, 500_000);
+
+    client.airdrop(wrapped_sol, 250_000).await?;
+    client.sync_native(spl_token::ID, wrapped_sol).await?;
+    assert_eq!(client.get_token_account(wrapped_sol).await?.amount, 750_000);
+}
+
+#[throws]
+#[tokio::test]
+pub async fn token_2022_extensions() {
+    use trdelnik_client::{
+        spl_token_2022::{
+            self,
+            extension::{
+                interest_bearing_mint::InterestBearingConfig,
+                permanent_delegate::PermanentDelegate,
+                transfer_fee::{TransferFeeAmount, TransferFeeConfig},
+                ExtensionType,
+            },
+        },
+        *,
+    };
+
+    let mut client = Client::with_program_test(Keypair::new(), ProgramTest::default()).await;
+    let authority = Keypair::new();
+    let alice = Keypair::new();
+    let bob = Keypair::new();
+
+    let mint = Keypair::new();
+    client
+        .create_token_2022_mint(
+            &mint,
+            authority.pubkey(),
+            None,
+            2,
+            &[
+                MintExtension::TransferFee {
+                    authority: Some(authority.pubkey()),
+                    withdraw_withheld_authority: Some(authority.pubkey()),
+                    basis_points: 100,
+                    maximum_fee: 1_000,
+                },
+                MintExtension::InterestBearing {
+                    rate_authority: Some(authority.pubkey()),
+                    rate: 500,
+                },
+                MintExtension::PermanentDelegate {
+                    delegate: authority.pubkey(),
+                },
+            ],
+        )
+        .await?;
+    assert_eq!(
+        client.get_mint_extension_types(mint.pubkey()).await?,
+        vec![
+            ExtensionType::TransferFeeConfig,
+            ExtensionType::InterestBearingConfig,
+            ExtensionType::PermanentDelegate,
+        ]
+    );
+    let transfer_fee = client
+        .get_mint_extension::<TransferFeeConfig>(mint.pubkey())
+        .await?;
+    assert_eq!(
+        u16::from(transfer_fee.newer_transfer_fee.transfer_fee_basis_points),
+        100
+    );
+    let interest = client
+        .get_mint_extension::<InterestBearingConfig>(mint.pubkey())
+        .await?;
+    assert_eq!(i16::from(interest.current_rate), 500);
+    let permanent_delegate = client
+        .get_mint_extension::<PermanentDelegate>(mint.pubkey())
+        .await?;
+    assert_eq!(
+        Option::<Pubkey>::from(permanent_delegate.delegate),
+        Some(authority.pubkey())
+    );
+
+    let alice_tokens = client
+        .create_associated_token_account(spl_token_2022::ID, &alice, mint.pubkey())
+        .await?;
+    let bob_tokens = Keypair::new();
+    client
+        .create_token_account(
+            spl_token_2022::ID,
+            &bob_tokens,
+            &mint.pubkey(),
+            &bob.pubkey(),
+        )
+        .await?;
+    client
+        .mint_tokens(
+            spl_token_2022::ID,
+            mint.pubkey(),
+            &authority,
+            alice_tokens,
+            10_000,
+        )
+        .await?;
+    assert_eq!(client.get_mint(mint.pubkey()).await?.supply, 10_000);
+
+    client
+        .transfer_tokens_checked(
+            spl_token_2022::ID,
+            alice_tokens,
+            mint.pubkey(),
+            bob_tokens.pubkey(),
+            &alice,
+            5_000,
+            2,
+        )
+        .await?;
+    assert_eq!(
+        client.get_token_account(bob_tokens.pubkey()).await?.amount,
+        4_950
+    );
+    let withheld = client
+        .get_token_account_extension::<TransferFeeAmount>(bob_tokens.pubkey())
+        .await?;
+    assert_eq!(u64::from(withheld.withheld_amount), 50);
+    assert_eq!(
+        client.get_token_balance(bob_tokens.pubkey()).await?.amount,
+        "4950"
+    );
+
+    // the permanent delegate can burn the tokens of any account
+    client
+        .burn_tokens(
+            spl_token_2022::ID,
+            alice_tokens,
+            mint.pubkey(),
+            &authority,
+            1_000,
+        )
+        .await?;
+    assert_eq!(client.get_token_account(alice_tokens).await?.amount, 4_000);
+
+    let non_transferable_mint = Keypair::new();
+    client
+        .create_token_2022_mint(
+            &non_transferable_mint,
+            authority.pubkey(),
+            None,
+            0,
+            &[MintExtension::NonTransferable],
+        )
+        .await?;
+    let alice_tokens = client
+        .create_associated_token_account(spl_token_2022::ID, &alice, non_transferable_mint.pubkey())
+        .await?;
+    let bob_tokens = client
+        .create_associated_token_account(spl_token_2022::ID, &bob, non_transferable_mint.pubkey())
+        .await?;
+    client
+        .mint_tokens(
+            spl_token_2022::ID,
+            non_transferable_mint.pubkey(),
+            &authority,
+            alice_tokens,
+            10,
+        )
+        .await?;
+    assert!(client
+        .transfer_tokens_checked(
+            spl_token_2022::ID,
+            alice_tokens,
+            non_transferable_mint.pubkey(),
+            bob_tokens,
+            &alice,
+            1,
+            0,
+        )
+        .await
+        .is_err());
+}
+
+#[throws]
+#[tokio::test]
+pub async fn set_account() {
+    use order_book::Market;
+    use trdelnik_client::{anchor_lang::Discriminator, solana_sdk::account::Account, *};
+
+    let client = Client::with_program_test(Keypair::new(), ProgramTest::default()).await;
+
+    let mock = Keypair::new().pubkey();
+    let account = Account {
+        lamports: 1_000_000,
+        data: vec![1, 2, 3],
+        owner: Keypair::new().pubkey(),
+        executable: false,
+        rent_epoch: 0,
+    };
+    client.set_account(mock, account.clone()).await?;
+    assert_eq!(client.get_account(mock).await?, Some(account));
+
+    let market = Keypair::new().pubkey();
+    let value = Market {
+        authority: client.payer().pubkey(),
+        tick_size: 100,
+    };
+    client.set_anchor_account(market, ID, &value).await?;
+    let account = client.get_account(market).await?.unwrap();
+    assert_eq!(account.owner, ID);
+    assert_eq!(account.data[..8], Market::DISCRIMINATOR);
+    assert_eq!(client.account_data::<Market>(market).await?, value);
+    // the accounts set directly are found among the program accounts
+    assert_eq!(
+        client.program_accounts::<Market>(ID, vec![]).await?,
+        vec![(market, value)]
+    );
+
+    // the accounts of a running cluster cannot be set
+    assert!(Client::new(Keypair::new())
+        .set_account(mock, Account::default())
+        .await
+        .is_err());
+}
+
+#[throws]
+#[tokio::test]
+pub async fn clock_manipulation() {
+    use std::time::Duration;
+    use trdelnik_client::*;
+
+    let client = Client::with_program_test(Keypair::new(), ProgramTest::default()).await;
+
+    let clock = client.get_clock().await?;
+    client.warp_to_slot(clock.slot + 1_000).await?;
+    assert_eq!(client.get_clock().await?.slot, clock.slot + 1_000);
+    assert!(client.warp_to_slot(clock.slot).await.is_err());
+
+    client.set_clock(1_700_000_000).await?;
+    assert_eq!(client.get_clock().await?.unix_timestamp, 1_700_000_000);
+
+    let clock = client.get_clock().await?;
+    client.advance_time(Duration::from_secs(60 * 60)).await?;
+    let advanced_clock = client.get_clock().await?;
+    assert_eq!(advanced_clock.unix_timestamp, 1_700_000_000 + 60 * 60);
+    assert_eq!(advanced_clock.slot, clock.slot + 60 * 60 * 1_000 / 400);
+
+    // the transactions are processed after the time travel
+    let wallet = Keypair::new();
+    client.airdrop(wallet.pubkey(), 1_000_000).await?;
+
+    let rent = client.get_rent().await?;
+    assert_eq!(rent.minimum_balance(0), 890_880);
+    let epoch_schedule = client.get_epoch_schedule().await?;
+    assert!(epoch_schedule.slots_per_epoch > 0);
+}
+
+#[throws]
+#[tokio::test]
+pub async fn account_snapshot_diff() {
+    use order_book::{Fees, Market, Pool, PoolState};
+    use trdelnik_client::*;
+
+    let idl: AnchorIdl = serde_json::from_value(serde_json::json!({
+        "version": "0.1.0",
+        "name": "order_book",
+        "instructions": [],
+        "accounts": [
+            {
+                "name": "Market",
+                "type": {
+                    "kind": "struct",
+                    "fields": [
+                        { "name": "authority", "type": "publicKey" },
+                        { "name": "tick_size", "type": "u64" }
+                    ]
+                }
+            },
+            {
+                "name": "Pool",
+                "type": {
+                    "kind": "struct",
+                    "fields": [
+                        { "name": "name", "type": "string" },
+                        { "name": "fees", "type": { "defined": "Fees" } },
+                        { "name": "state", "type": { "defined": "PoolState" } },
+                        { "name": "holders", "type": { "vec": "publicKey" } }
+                    ]
+                }
+            }
+        ],
+        "types": [
+            {
+                "name": "Fees",
+                "type": {
+                    "kind": "struct",
+                    "fields": [
+                        { "name": "maker", "type": "u16" },
+                        { "name": "taker", "type": "u16" }
+                    ]
+                }
+            },
+            {
+                "name": "PoolState",
+                "type": {
+                    "kind": "enum",
+                    "variants": [
+                        { "name": "Open" },
+                        { "name": "Closed", "fields": [{ "name": "at", "type": "i64" }] }
+                    ]
+                }
+            }
+        ]
+    }))?;
+
+    let client = Client::with_program_test(Keypair::new(), ProgramTest::default()).await;
+    let (wallet, market, unchanged, created) = (
+        Keypair::new(),
+        Keypair::new().pubkey(),
+        Keypair::new().pubkey(),
+        Keypair::new(),
+    );
+    client.airdrop(wallet.pubkey(), 1_000_000_000).await?;
+    let mut value = Market {
+        authority: wallet.pubkey(),
+        tick_size: 100,
+    };
+    client.set_anchor_account(market, ID, &value).await?;
+    let pool = Keypair::new().pubkey();
+    let mut pool_value = Pool {
+        name: "a: b, c".to_owned(),
+        fees: Fees { maker: 1, taker: 5 },
+        state: PoolState::Open,
+        holders: Vec::new(),
+    };
+    client.set_anchor_account(pool, ID, &pool_value).await?;
+    let addresses = [wallet.pubkey(), market, unchanged, created.pubkey(), pool];
+
+    let before = client.snapshot(&addresses).await?;
+    value.tick_size = 250;
+    client.set_anchor_account(market, ID, &value).await?;
+    client.create_account(&created, 1_000_000, 3, &ID).await?;
+    client.airdrop(wallet.pubkey(), 500).await?;
+    pool_value.name = "a: b, d".to_owned();
+    pool_value.fees.taker = 7;
+    pool_value.state = PoolState::Closed { at: 3 };
+    pool_value.holders.push(wallet.pubkey());
+    client.set_anchor_account(pool, ID, &pool_value).await?;
+    let after = client.snapshot(&addresses).await?;
+
+    let diff = before.diff(&after).with_idl_fields(&idl);
+    assert_eq!(diff.accounts.len(), 4);
+    assert!(diff.account(&unchanged).is_none());
+
+    let wallet_diff = diff.account(&wallet.pubkey()).unwrap();
+    assert_eq!(wallet_diff.status, AccountStatus::Modified);
+    assert_eq!(wallet_diff.lamports_delta, 500);
+    assert!(wallet_diff.bytes.is_empty());
+
+    let market_diff = diff.account(&market).unwrap();
+    assert_eq!(market_diff.lamports_delta, 0);
+    assert_eq!(market_diff.bytes.len(), 1);
+    assert_eq!(market_diff.bytes[0].offset, 8 + 32);
+    assert_eq!(market_diff.fields.len(), 1);
+    assert_eq!(market_diff.fields[0].account_type, "Market");
+    assert_eq!(market_diff.fields[0].name, "tick_size");
+    assert_eq!(market_diff.fields[0].before.as_deref(), Some("100"));
+    assert_eq!(market_diff.fields[0].after.as_deref(), Some("250"));
+
+    let created_diff = diff.account(&created.pubkey()).unwrap();
+    assert_eq!(created_diff.status, AccountStatus::Created);
+    assert_eq!(created_diff.lamports_delta, 1_000_000);
+    assert_eq!(
+        created_diff.owner,
+        Some(Change {
+            before: None,
+            after: Some(ID.to_string())
+        })
+    );
+    assert_eq!(
+        created_diff.data_len,
+        Some(Change {
+            before: None,
+            after: Some(3)
+        })
+    );
+
+    let pool_diff = diff.account(&pool).unwrap();
+    let fields = pool_diff
+        .fields
+        .iter()
+        .map(|field| {
+            (
+                field.name.as_str(),
+                field.before.as_deref(),
+                field.after.as_deref(),
+            )
+        })
+        .collect::<Vec<_>>();
+    let holder = wallet.pubkey().to_string();
+    assert_eq!(
+        fields,
+        vec![
+            ("name", Some("\"a: b, c\""), Some("\"a: b, d\"")),
+            ("fees.taker", Some("5"), Some("7")),
+            ("state", Some("Open"), Some("Closed")),
+            ("state.at", None, Some("3")),
+            ("holders[0]", None, Some(holder.as_str())),
+            ("holders", Some("[]"), None),
+        ]
+    );
+
+    let printed = diff.to_string();
+    assert!(printed.contains("Lamports: +500"));
+    assert!(printed.contains("Field tick_size: 100 -> 250"));
+    diff.print_named("market update");
+
+    let json = serde_json::to_value(&diff)?;
+    assert_eq!(json["accounts"][1]["address"], market.to_string());
+    assert_eq!(json["accounts"][1]["fields"][0]["after"], "250");
+    let snapshot_json = serde_json::to_string(&after)?;
+    assert_eq!(
+        serde_json::from_str::<AccountSnapshot>(&snapshot_json)?,
+        after
+    );
+}
+
+#[throws]
+#[tokio::test]
+pub async fn durable_nonce_offline_signing() {
+    use trdelnik_client::{solana_sdk::system_instruction, *};
+
+    let mut client = Client::with_program_test(Keypair::new(), ProgramTest::default()).await;
+    let (alice, bob, recipient, nonce) = (
+        Keypair::new(),
+        Keypair::new(),
+        Keypair::new(),
+        Keypair::new(),
+    );
+    client.airdrop(alice.pubkey(), 1_000_000_000).await?;
+    client.airdrop(bob.pubkey(), 1_000_000_000).await?;
+
+    client.create_nonce_account(&nonce, alice.pubkey()).await?;
+    let nonce_data = client.get_nonce_data(nonce.pubkey()).await?;
+    assert_eq!(nonce_data.authority, alice.pubkey());
+
+    // alice pays the fee and advances the nonce, bob transfers the lamports
+    let mut transaction = client
+        .nonce_transaction(
+            &[system_instruction::transfer(
+                &bob.pubkey(),
+                &recipient.pubkey(),
+                1_000_000,
+            )],
+            nonce.pubkey(),
+            alice.pubkey(),
+            alice.pubkey(),
+        )
+        .await?;
+    assert_eq!(
+        transaction.transaction().message.recent_blockhash,
+        nonce_data.blockhash()
+    );
+    transaction.sign(&[&alice])?;
+    assert_eq!(transaction.missing_signers(), vec![bob.pubkey()]);
+    assert!(client.send_offline_transaction(&transaction).await.is_err());
+
+    // the offline signer signs the serialized copy
+    let mut bobs_copy = OfflineTransaction::from_base64(&transaction.to_base64()?)?;
+    bobs_copy.sign(&[&bob])?;
+    let bobs_copy = OfflineTransaction::from_base58(&bobs_copy.to_base58()?)?;
+
+    transaction.merge(&bobs_copy)?;
+    assert!(transaction.is_fully_signed());
+    let other = client
+        .nonce_transaction(&[], nonce.pubkey(), alice.pubkey(), alice.pubkey())
+        .await?;
+    assert!(transaction.clone().merge(&other).is_err());
+
+    client.send_offline_transaction(&transaction).await?;
+    assert_eq!(client.get_balance(&recipient.pubkey()).await?, 1_000_000);
+    assert_ne!(
+        client.get_nonce_data(nonce.pubkey()).await?.blockhash(),
+        nonce_data.blockhash()
+    );
+    // the nonce has been advanced, so the transaction cannot be replayed
+    assert!(client.send_offline_transaction(&transaction).await.is_err());
+
+    let nonce_data = client.get_nonce_data(nonce.pubkey()).await?;
+    client.advance_nonce_account(nonce.pubkey(), &alice).await?;
+    assert_ne!(
+        client.get_nonce_data(nonce.pubkey()).await?.blockhash(),
+        nonce_data.blockhash()
+    );
+}
+
+#[throws]
+#[tokio::test]
+pub async fn send_many() {
+    use trdelnik_client::{solana_sdk::system_instruction, *};
+
+    let mut client = Client::with_program_test(Keypair::new(), ProgramTest::default()).await;
+    let payer = client.payer().pubkey();
+    let recipients = (0..10).map(|_| Keypair::new()).collect::<Vec<_>>();
+    let poor = Keypair::new();
+
+    let mut transactions = recipients
+        .iter()
+        .enumerate()
+        .map(|(index, recipient)| {
+            (
+                vec![system_instruction::transfer(
+                    &payer,
+                    &recipient.pubkey(),
+                    1_000_000 + index as u64,
+                )],
+                vec![],
+            )
+        })
+        .collect::<Vec<_>>();
+    // the poor signer cannot pay the transfer
+    transactions.insert(
+        5,
+        (
+            vec![system_instruction::transfer(
+                &poor.pubkey(),
+                &payer,
+                1_000_000,
+            )],
+            vec![&poor],
+        ),
+    );
+
+    let outcomes = client.send_many(transactions, 4).await;
+    assert_eq!(outcomes.len(), 11);
+    assert!(outcomes[5].is_err());
+    let outcomes = outcomes
+        .into_iter()
+        .enumerate()
+        .filter(|(index, _)| *index != 5)
+        .map(|(_, outcome)| outcome)
+        .collect::<Result<Vec<_>, _>>()?;
+    for ((index, recipient), outcome) in recipients.iter().enumerate().zip(&outcomes) {
+        assert_eq!(outcome.instructions[0].accounts[1], recipient.pubkey());
+        assert_eq!(
+            client.get_balance(&recipient.pubkey()).await?,
+            1_000_000 + index as u64
+        );
+    }
+}
+
+#[throws]
+#[tokio::test]
+pub async fn send_many_confirms_together() {
+    use solana_transaction_status::{
+        EncodedTransaction, EncodedTransactionWithStatusMeta, TransactionBinaryEncoding,
+        TransactionStatusMeta, UiTransactionStatusMeta,
+    };
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    };
+    use trdelnik_client::{
+        anchor_client::solana_client::{
+            client_error::Result as RpcResult,
+            nonblocking::rpc_client::RpcClient,
+            rpc_client::RpcClientConfig,
+            rpc_request::RpcRequest,
+            rpc_sender::{RpcSender, RpcTransportStats},
+        },
+        solana_sdk::{
+            commitment_config::CommitmentConfig, hash::Hash, system_instruction,
+            transaction::VersionedTransaction,
+        },
+        *,
+    };
+
+    /// Confirms the received transactions on the second status poll and counts the polls.
+    #[derive(Default)]
+    struct FakeValidator {
+        transactions: Mutex<Vec<VersionedTransaction>>,
+        status_polls: AtomicUsize,
+    }
+
+    struct FakeSender(Arc<FakeValidator>);
+
+    #[async_trait::async_trait]
+    impl RpcSender for FakeSender {
+        async fn send(
+            &self,
+            request: RpcRequest,
+            params: serde_json::Value,
+        ) -> RpcResult<serde_json::Value> {
+            let validator = &self.0;
+            Ok(match request {
+                RpcRequest::GetVersion => serde_json::json!({ "solana-core": "1.16.9" }),
+                RpcRequest::GetLatestBlockhash => serde_json::json!({
+                    "context": { "slot": 1 },
+                    "value": { "blockhash": Hash::default().to_string(), "lastValidBlockHeight": 100 },
+                }),
+                RpcRequest::SendTransaction => {
+                    let data = base64::decode(params[0].as_str().unwrap()).unwrap();
+                    let transaction: VersionedTransaction = bincode::deserialize(&data).unwrap();
+                    let signature = transaction.signatures[0].to_string();
+                    validator.transactions.lock().unwrap().push(transaction);
+                    serde_json::json!(signature)
+                }
+                RpcRequest::GetSignatureStatuses => {
+                    let poll = validator.status_polls.fetch_add(1, Ordering::SeqCst);
+                    let statuses = params[0]
+                        .as_array()
+                        .unwrap()
+                        .iter()
+                        .map(|_| {
+                            (poll > 0).then(|| {
+                                serde_json::json!({
+                                    "slot": 1,
+                                    "confirmations": null,
+                                    "err": null,
+                                    "status": { "Ok": null },
+                                    "confirmationStatus": "finalized",
+                                })
+                            })
+                        })
+                        .collect::<Vec<_>>();
+                    serde_json::json!({ "context": { "slot": 1 }, "value": statuses })
+                }
+                RpcRequest::GetTransaction => {
+                    let signature = params[0].as_str().unwrap();
+                    let transaction = validator
+                        .transactions
+                        .lock()
+                        .unwrap()
+                        .iter()
+                        .find(|transaction| transaction.signatures[0].to_string() == signature)
+                        .cloned()
+                        .unwrap();
+                    let accounts = transaction.message.static_account_keys().len();
+                    serde_json::to_value(EncodedConfirmedTransactionWithStatusMeta {
+                        slot: 1,
+                        transaction: EncodedTransactionWithStatusMeta {
+                            transaction: EncodedTransaction::Binary(
+                                base64::encode(bincode::serialize(&transaction).unwrap()),
+                                TransactionBinaryEncoding::Base64,
+                            ),
+                            meta: Some(UiTransactionStatusMeta::from(TransactionStatusMeta {
+                                status: Ok(()),
+                                fee: 5_000,
+                                pre_balances: vec![0; accounts],
+                                post_balances: vec![0; accounts],
+                                log_messages: Some(Vec::new()),
+                                ..TransactionStatusMeta::default()
+                            })),
+                            version: Some(transaction.version()),
+                        },
+                        block_time: None,
+                    })
+                    .unwrap()
+                }
+                request => panic!("unexpected request {request}"),
+            })
+        }
+
+        fn get_transport_stats(&self) -> RpcTransportStats {
+            RpcTransportStats::default()
+        }
+
+        fn url(&self) -> String {
+            "fake".to_string()
+        }
+    }
+
+    let validator = Arc::new(FakeValidator::default());
+    let client = Client::with_rpc_client(
+        Keypair::new(),
+        RpcClient::new_sender(
+            FakeSender(validator.clone()),
+            RpcClientConfig::with_commitment(CommitmentConfig::confirmed()),
+        ),
+    );
+    let payer = client.payer().pubkey();
+    let transactions = (1..=10)
+        .map(|lamports| {
+            (
+                vec![system_instruction::transfer(
+                    &payer,
+                    &Pubkey::new_unique(),
+                    lamports,
+                )],
+                vec![],
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let outcomes = client.send_many(transactions, 4).await;
+    assert_eq!(outcomes.len(), 10);
+    for outcome in outcomes {
+        outcome?;
+    }
+    assert_eq!(validator.transactions.lock().unwrap().len(), 10);
+    // all transactions are confirmed by the same two polls, not one poll per transaction
+    assert_eq!(validator.status_polls.load(Ordering::SeqCst), 2);
+}
+
+#[throws]
+#[tokio::test]
+pub async fn lock_contention() {
+    use trdelnik_client::{solana_sdk::system_instruction, *};
+
+    let (alice, bob, carol, pool) = (
+        Keypair::new().pubkey(),
+        Keypair::new().pubkey(),
+        Keypair::new().pubkey(),
+        Keypair::new().pubkey(),
+    );
+    let report = ContentionAnalyzer::new()
+        .with_instruction(
+            "alice -> pool",
+            system_instruction::transfer(&alice, &pool, 1),
+        )
+        .with_instruction(
+            "bob -> carol",
+            system_instruction::transfer(&bob, &carol, 1),
+        )
+        .with_instruction(
+            "carol -> pool",
+            system_instruction::transfer(&carol, &pool, 1),
+        )
+        .with_instruction(
+            "alice -> bob",
+            system_instruction::transfer(&alice, &bob, 1),
+        )
+        .analyze();
+    assert_eq!(
+        report.conflicts,
+        vec![
+            Conflict {
+                first: 0,
+                second: 2,
+                accounts: vec![pool]
+            },
+            Conflict {
+                first: 0,
+                second: 3,
+                accounts: vec![alice]
+            },
+            Conflict {
+                first: 1,
+                second: 2,
+                accounts: vec![carol]
+            },
+            Conflict {
+                first: 1,
+                second: 3,
+                accounts: vec![bob]
+            },
+        ]
+    );
+    assert_eq!(report.batches, vec![vec![0, 1], vec![2, 3]]);
+    assert_eq!(report.parallelism(), 2.0);
+    assert_eq!(report.hot_accounts().len(), 4);
+    assert!(report
+        .to_string()
+        .ends_with("4 item(s) in 2 sequential batch(es), parallelism 2.00"));
+
+    // the observed transactions share the fee payer
+    let client = Client::with_program_test(Keypair::new(), ProgramTest::default()).await;
+    let payer = client.payer().pubkey();
+    let outcomes = client
+        .send_many(
+            vec![
+                (
+                    vec![system_instruction::transfer(&payer, &alice, 1_000_000)],
+                    vec![],
+                ),
+                (
+                    vec![system_instruction::transfer(&payer, &bob, 1_000_000)],
+                    vec![],
+                ),
+            ],
+            2,
+        )
+        .await;
+    let mut analyzer = ContentionAnalyzer::new();
+    for (index, outcome) in outcomes.into_iter().enumerate() {
+        analyzer = analyzer.with_outcome(index.to_string(), &outcome?)?;
+    }
+    let report = analyzer.analyze();
+    assert_eq!(report.conflicts[0].accounts, vec![payer]);
+    assert_eq!(report.batches.len(), 2);
+}
+
+#[throws]
+#[tokio::test]
+pub async fn stake_and_vote_helpers() {
+    use trdelnik_client::{
+        solana_sdk::{
+            native_token::LAMPORTS_PER_SOL,
+            stake::state::{Authorized, Lockup, StakeState},
+        },
+        *,
+    };
+
+    let client = Client::with_program_test(Keypair::new(), ProgramTest::default()).await;
+    let (vote, node, staker, stake, split) = (
+        Keypair::new(),
+        Keypair::new(),
+        Keypair::new(),
+        Keypair::new(),
+        Keypair::new(),
+    );
+
+    client
+        .create_vote_account(&vote, &node, node.pubkey(), client.payer().pubkey(), 10)
+        .await?;
+    let vote_state = client.get_vote_state(vote.pubkey()).await?;
+    assert_eq!(vote_state.node_pubkey, node.pubkey());
+    assert_eq!(vote_state.commission, 10);
+
+    client
+        .create_stake_account(&stake, 10 * LAMPORTS_PER_SOL)
+        .await?;
+    assert_eq!(
+        client.get_stake_state(stake.pubkey()).await?,
+        StakeState::Uninitialized
+    );
+    client
+        .initialize_stake_account(
+            stake.pubkey(),
+            Authorized::auto(&staker.pubkey()),
+            Lockup::default(),
+        )
+        .await?;
+    assert!(matches!(
+        client.get_stake_state(stake.pubkey()).await?,
+        StakeState::Initialized(meta) if meta.authorized.staker == staker.pubkey()
+    ));
+
+    client
+        .split_stake(stake.pubkey(), &staker, 3 * LAMPORTS_PER_SOL, &split)
+        .await?;
+    assert!(matches!(
+        client.get_stake_state(split.pubkey()).await?,
+        StakeState::Initialized(_)
+    ));
+    client
+        .merge_stake(stake.pubkey(), split.pubkey(), &staker)
+        .await?;
+    assert_eq!(client.get_account(split.pubkey()).await?, None);
+
+    client
+        .delegate_stake(stake.pubkey(), vote.pubkey(), &staker)
+        .await?;
+    let delegation = match client.get_stake_state(stake.pubkey()).await? {
+        StakeState::Stake(_, stake) => stake.delegation,
+        state => panic!("unexpected stake state {state:?}"),
+    };
+    assert_eq!(delegation.voter_pubkey, vote.pubkey());
+
+    client.deactivate_stake(stake.pubkey(), &staker).await?;
+    let epoch = client.get_clock().await?.epoch;
+    assert!(matches!(
+        client.get_stake_state(stake.pubkey()).await?,
+        StakeState::Stake(_, stake) if stake.delegation.deactivation_epoch == epoch
+    ));
+
+    // the stake activated and deactivated in the same epoch is withdrawable right away
+    let recipient = Keypair::new().pubkey();
+    client
+        .withdraw_stake(stake.pubkey(), &staker, recipient, LAMPORTS_PER_SOL)
+        .await?;
+    assert_eq!(
+        client.get_account(recipient).await?.unwrap().lamports,
+        LAMPORTS_PER_SOL
+    );
+}
+
+#[throws]
+#[tokio::test]
+pub async fn checkout_revision_of_nested_workspace() {
+    use std::process::Command;
+    use trdelnik_client::*;
+
+    // a repository with the Anchor workspace in a subdirectory
+    let repository = std::env::temp_dir().join(format!("trdelnik-{}", Pubkey::new_unique()));
+    let workspace = repository.join("examples/escrow");
+    std::fs::create_dir_all(&workspace)?;
+    let git = |args: &[&str]| {
+        let status = Command::new("git")
+            .args([
+                "-c",
+                "user.name=trdelnik",
+                "-c",
+                "user.email=trdelnik@localhost",
+            ])
+            .args(args)
+            .current_dir(&repository)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    };
+    git(&["init", "-q"]);
+    std::fs::write(workspace.join("Anchor.toml"), "[programs.localnet]\n")?;
+    git(&["add", "-A"]);
+    git(&["commit", "-q", "-m", "anchor workspace"]);
+    std::fs::remove_file(workspace.join("Anchor.toml"))?;
+
+    let reader = Reader::with_root(format!("{}/", workspace.display()));
+    let checkout = reader.checkout_revision("HEAD").await?;
+    assert!(checkout.join("Anchor.toml").is_file());
+    assert!(checkout.ends_with("examples/escrow/"));
+    // the worktree is reused
+    assert_eq!(reader.checkout_revision("HEAD").await?, checkout);
+    assert!(reader.checkout_revision("no-such-revision").await.is_err());
+
+    std::fs::remove_dir_all(&repository)?;
+}
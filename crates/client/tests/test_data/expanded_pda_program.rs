@@ -0,0 +1,117 @@
+pub static ID: anchor_lang::solana_program::pubkey::Pubkey =
+    anchor_lang::solana_program::pubkey::Pubkey::new_from_array([
+        1u8, 1u8, 1u8, 1u8, 1u8, 1u8, 1u8, 1u8, 1u8, 1u8, 1u8, 1u8, 1u8, 1u8, 1u8, 1u8, 1u8, 1u8,
+        1u8, 1u8, 1u8, 1u8, 1u8, 1u8, 1u8, 1u8, 1u8, 1u8, 1u8, 1u8, 1u8, 1u8,
+    ]);
+pub const CONFIG_SEED: &[u8] = b"config";
+mod __private {
+    pub mod __global {
+        use super::*;
+        #[inline(never)]
+        pub fn deposit(
+            __program_id: &Pubkey,
+            __accounts: &[AccountInfo],
+            __ix_data: &[u8],
+        ) -> anchor_lang::Result<()> {
+            let ix = instruction::Deposit::deserialize(&mut &__ix_data[..])
+                .map_err(|_| anchor_lang::error::ErrorCode::InstructionDidNotDeserialize)?;
+            let instruction::Deposit { name } = ix;
+            let mut __bumps = std::collections::BTreeMap::new();
+            let mut __reallocs = std::collections::BTreeSet::new();
+            let mut __remaining_accounts: &[AccountInfo] = __accounts;
+            let mut __accounts = Deposit::try_accounts(
+                __program_id,
+                &mut __remaining_accounts,
+                __ix_data,
+                &mut __bumps,
+                &mut __reallocs,
+            )?;
+            let result = vault::deposit(
+                anchor_lang::context::Context::new(
+                    __program_id,
+                    &mut __accounts,
+                    __remaining_accounts,
+                    __bumps,
+                ),
+                name,
+            )?;
+            __accounts.exit(__program_id)
+        }
+        #[inline(never)]
+        pub fn withdraw(
+            __program_id: &Pubkey,
+            __accounts: &[AccountInfo],
+            __ix_data: &[u8],
+        ) -> anchor_lang::Result<()> {
+            let ix = instruction::Withdraw::deserialize(&mut &__ix_data[..])
+                .map_err(|_| anchor_lang::error::ErrorCode::InstructionDidNotDeserialize)?;
+            let instruction::Withdraw { amount } = ix;
+            let mut __bumps = std::collections::BTreeMap::new();
+            let mut __reallocs = std::collections::BTreeSet::new();
+            let mut __remaining_accounts: &[AccountInfo] = __accounts;
+            let mut __accounts = Withdraw::try_accounts(
+                __program_id,
+                &mut __remaining_accounts,
+                __ix_data,
+                &mut __bumps,
+                &mut __reallocs,
+            )?;
+            let result = vault::withdraw(
+                anchor_lang::context::Context::new(
+                    __program_id,
+                    &mut __accounts,
+                    __remaining_accounts,
+                    __bumps,
+                ),
+                amount,
+            )?;
+            __accounts.exit(__program_id)
+        }
+    }
+}
+pub mod instruction {
+    use super::*;
+    pub struct Deposit {
+        pub name: String,
+    }
+    pub struct Withdraw {
+        pub amount: u64,
+    }
+}
+#[instruction(name : String)]
+pub struct Deposit<'info> {
+    pub authority: Signer<'info>,
+    pub pool: Account<'info, Pool>,
+    #[account(mut, seeds = [b"vault", authority.key().as_ref(), pool.mint.as_ref(),
+    name.as_bytes()], bump = pool.bump @ VaultError::InvalidBump)]
+    pub vault: AccountInfo<'info>,
+    #[account(seeds = [crate::CONFIG_SEED], bump)]
+    pub config: AccountInfo<'info>,
+}
+pub(crate) mod __client_accounts_deposit {
+    use super::*;
+    use anchor_lang::prelude::borsh;
+    pub struct Deposit {
+        pub authority: anchor_lang::solana_program::pubkey::Pubkey,
+        pub pool: anchor_lang::solana_program::pubkey::Pubkey,
+        pub vault: anchor_lang::solana_program::pubkey::Pubkey,
+        pub config: anchor_lang::solana_program::pubkey::Pubkey,
+    }
+}
+#[instruction(amount : u64)]
+pub struct Withdraw<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut, seeds = [b"vault", authority.key().as_ref()], bump)]
+    pub vault: AccountInfo<'info>,
+    #[account(seeds = [crate::CONFIG_SEED], bump)]
+    pub config: AccountInfo<'info>,
+}
+pub(crate) mod __client_accounts_withdraw {
+    use super::*;
+    use anchor_lang::prelude::borsh;
+    pub struct Withdraw {
+        pub authority: anchor_lang::solana_program::pubkey::Pubkey,
+        pub vault: anchor_lang::solana_program::pubkey::Pubkey,
+        pub config: anchor_lang::solana_program::pubkey::Pubkey,
+    }
+}
@@ -0,0 +1,152 @@
+// DO NOT EDIT - automatically generated file (except `use` statements inside the `*_instruction` module
+pub mod vault_instruction {
+    use trdelnik_client::*;
+    pub static PROGRAM_ID: Pubkey = Pubkey::new_from_array([
+        1u8, 1u8, 1u8, 1u8, 1u8, 1u8, 1u8, 1u8, 1u8, 1u8, 1u8, 1u8, 1u8, 1u8, 1u8, 1u8, 1u8, 1u8,
+        1u8, 1u8, 1u8, 1u8, 1u8, 1u8, 1u8, 1u8, 1u8, 1u8, 1u8, 1u8, 1u8, 1u8,
+    ]);
+    pub async fn deposit(
+        client: &Client,
+        i_name: String,
+        a_authority: anchor_lang::solana_program::pubkey::Pubkey,
+        a_pool: anchor_lang::solana_program::pubkey::Pubkey,
+        a_vault: anchor_lang::solana_program::pubkey::Pubkey,
+        a_config: anchor_lang::solana_program::pubkey::Pubkey,
+        signers: impl IntoIterator<Item = Keypair> + Send + 'static,
+    ) -> Result<TransactionOutcome, ClientError> {
+        Ok(client
+            .send_instruction(
+                PROGRAM_ID,
+                vault::instruction::Deposit { name: i_name },
+                vault::accounts::Deposit {
+                    authority: a_authority,
+                    pool: a_pool,
+                    vault: a_vault,
+                    config: a_config,
+                },
+                signers,
+            )
+            .await?)
+    }
+    pub async fn deposit_with_options(
+        client: &Client,
+        i_name: String,
+        a_authority: anchor_lang::solana_program::pubkey::Pubkey,
+        a_pool: anchor_lang::solana_program::pubkey::Pubkey,
+        a_vault: anchor_lang::solana_program::pubkey::Pubkey,
+        a_config: anchor_lang::solana_program::pubkey::Pubkey,
+        signers: impl IntoIterator<Item = Keypair> + Send + 'static,
+        options: &SendOptions,
+    ) -> Result<TransactionOutcome, ClientError> {
+        Ok(client
+            .send_instruction_with_options(
+                PROGRAM_ID,
+                vault::instruction::Deposit { name: i_name },
+                vault::accounts::Deposit {
+                    authority: a_authority,
+                    pool: a_pool,
+                    vault: a_vault,
+                    config: a_config,
+                },
+                signers,
+                options,
+            )
+            .await?)
+    }
+    pub fn deposit_ix(
+        i_name: String,
+        a_authority: anchor_lang::solana_program::pubkey::Pubkey,
+        a_pool: anchor_lang::solana_program::pubkey::Pubkey,
+        a_vault: anchor_lang::solana_program::pubkey::Pubkey,
+        a_config: anchor_lang::solana_program::pubkey::Pubkey,
+    ) -> Instruction {
+        Instruction {
+            program_id: PROGRAM_ID,
+            data: vault::instruction::Deposit { name: i_name }.data(),
+            accounts: vault::accounts::Deposit {
+                authority: a_authority,
+                pool: a_pool,
+                vault: a_vault,
+                config: a_config,
+            }
+            .to_account_metas(None),
+        }
+    }
+    pub async fn withdraw(
+        client: &Client,
+        i_amount: u64,
+        a_authority: anchor_lang::solana_program::pubkey::Pubkey,
+        a_vault: anchor_lang::solana_program::pubkey::Pubkey,
+        a_config: anchor_lang::solana_program::pubkey::Pubkey,
+        signers: impl IntoIterator<Item = Keypair> + Send + 'static,
+    ) -> Result<TransactionOutcome, ClientError> {
+        Ok(client
+            .send_instruction(
+                PROGRAM_ID,
+                vault::instruction::Withdraw { amount: i_amount },
+                vault::accounts::Withdraw {
+                    authority: a_authority,
+                    vault: a_vault,
+                    config: a_config,
+                },
+                signers,
+            )
+            .await?)
+    }
+    pub async fn withdraw_with_options(
+        client: &Client,
+        i_amount: u64,
+        a_authority: anchor_lang::solana_program::pubkey::Pubkey,
+        a_vault: anchor_lang::solana_program::pubkey::Pubkey,
+        a_config: anchor_lang::solana_program::pubkey::Pubkey,
+        signers: impl IntoIterator<Item = Keypair> + Send + 'static,
+        options: &SendOptions,
+    ) -> Result<TransactionOutcome, ClientError> {
+        Ok(client
+            .send_instruction_with_options(
+                PROGRAM_ID,
+                vault::instruction::Withdraw { amount: i_amount },
+                vault::accounts::Withdraw {
+                    authority: a_authority,
+                    vault: a_vault,
+                    config: a_config,
+                },
+                signers,
+                options,
+            )
+            .await?)
+    }
+    pub fn withdraw_ix(
+        i_amount: u64,
+        a_authority: anchor_lang::solana_program::pubkey::Pubkey,
+        a_vault: anchor_lang::solana_program::pubkey::Pubkey,
+        a_config: anchor_lang::solana_program::pubkey::Pubkey,
+    ) -> Instruction {
+        Instruction {
+            program_id: PROGRAM_ID,
+            data: vault::instruction::Withdraw { amount: i_amount }.data(),
+            accounts: vault::accounts::Withdraw {
+                authority: a_authority,
+                vault: a_vault,
+                config: a_config,
+            }
+            .to_account_metas(None),
+        }
+    }
+    pub fn find_deposit_vault_address(
+        authority: Pubkey,
+        pool_mint: &[u8],
+        name: String,
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[b"vault", authority.as_ref(), pool_mint, name.as_bytes()],
+            &PROGRAM_ID,
+        )
+    }
+    pub fn find_config_address() -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[vault::CONFIG_SEED], &PROGRAM_ID)
+    }
+    pub fn find_withdraw_vault_address(authority: Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"vault", authority.as_ref()], &PROGRAM_ID)
+    }
+}
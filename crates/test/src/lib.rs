@@ -11,6 +11,8 @@ use syn::{parse_macro_input, spanned::Spanned, AttributeArgs, ItemFn};
 struct MacroArgs {
     #[darling(default)]
     root: Option<String>,
+    #[darling(default)]
+    accounts: Option<syn::Path>,
 }
 
 /// The macro starts the Solana validator (localnet), runs your program test and then shuts down the validator.
@@ -19,6 +21,9 @@ struct MacroArgs {
 /// - Async support is provided by Tokio: [tokio::test(flavor = "multi_thread")](https://docs.rs/tokio/latest/tokio/attr.test.html).
 /// - The macro accepts one optional argument `root` with the default value `"../../"`.
 ///      - Example: `#[trdelnik_test(root = "../../")]`
+/// - The optional argument `accounts` is a path to a function returning `anyhow::Result<Vec<(Pubkey, Account)>>`,
+///   the accounts are created in the genesis of the validator (see `Commander::start_localnet_with_accounts`).
+///      - Example: `#[trdelnik_test(accounts = "genesis_accounts")]`
 /// - You can see the macro expanded in the crate's tests.
 ///
 /// # Example
@@ -50,6 +55,9 @@ pub fn trdelnik_test(args: TokenStream, input: TokenStream) -> TokenStream {
         }
     };
     let root = macro_args.root.unwrap_or_else(|| "../../".to_owned());
+    let with_accounts = macro_args
+        .accounts
+        .map(|accounts| quote::quote!(.with_accounts(#accounts()?)));
 
     let input_fn: ItemFn =
         syn::parse(input).expect("'trdelnik_test' attribute is applicable only to async fn");
@@ -69,7 +77,7 @@ pub fn trdelnik_test(args: TokenStream, input: TokenStream) -> TokenStream {
         #[trdelnik_client::tokio::test(flavor = "multi_thread")]
         #[trdelnik_client::serial_test::serial]
         async fn #input_fn_name(#input_fn_inputs) -> trdelnik_client::anyhow::Result<()> {
-            let mut tester = trdelnik_client::Tester::with_root(#root);
+            let mut tester = trdelnik_client::Tester::with_root(#root)#with_accounts;
             let localnet_handle = tester.before().await?;
             let test = async {
                 #input_fn_body
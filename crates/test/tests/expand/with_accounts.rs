@@ -0,0 +1,3 @@
+#[trdelnik_test::trdelnik_test(accounts = "genesis_accounts")]
+async fn test_with_genesis_accounts() {
+}
@@ -17,7 +17,7 @@ pub mod escrow_instruction {
         a_system_program: anchor_lang::solana_program::pubkey::Pubkey,
         a_token_program: anchor_lang::solana_program::pubkey::Pubkey,
         signers: impl IntoIterator<Item = Keypair> + Send + 'static,
-    ) -> Result<EncodedConfirmedTransactionWithStatusMeta, ClientError> {
+    ) -> Result<TransactionOutcome, ClientError> {
         Ok(client
             .send_instruction(
                 PROGRAM_ID,
@@ -37,6 +37,39 @@ pub mod escrow_instruction {
             )
             .await?)
     }
+    pub async fn initialize_escrow_with_options(
+        client: &Client,
+        i_initializer_amount: u64,
+        i_taker_amount: u64,
+        a_initializer: anchor_lang::solana_program::pubkey::Pubkey,
+        a_initializer_deposit_token_account: anchor_lang::solana_program::pubkey::Pubkey,
+        a_initializer_receive_token_account: anchor_lang::solana_program::pubkey::Pubkey,
+        a_escrow_account: anchor_lang::solana_program::pubkey::Pubkey,
+        a_system_program: anchor_lang::solana_program::pubkey::Pubkey,
+        a_token_program: anchor_lang::solana_program::pubkey::Pubkey,
+        signers: impl IntoIterator<Item = Keypair> + Send + 'static,
+        options: &SendOptions,
+    ) -> Result<TransactionOutcome, ClientError> {
+        Ok(client
+            .send_instruction_with_options(
+                PROGRAM_ID,
+                escrow::instruction::InitializeEscrow {
+                    initializer_amount: i_initializer_amount,
+                    taker_amount: i_taker_amount,
+                },
+                escrow::accounts::InitializeEscrow {
+                    initializer: a_initializer,
+                    initializer_deposit_token_account: a_initializer_deposit_token_account,
+                    initializer_receive_token_account: a_initializer_receive_token_account,
+                    escrow_account: a_escrow_account,
+                    system_program: a_system_program,
+                    token_program: a_token_program,
+                },
+                signers,
+                options,
+            )
+            .await?)
+    }
     pub fn initialize_escrow_ix(
         i_initializer_amount: u64,
         i_taker_amount: u64,
@@ -73,7 +106,7 @@ pub mod escrow_instruction {
         a_escrow_account: anchor_lang::solana_program::pubkey::Pubkey,
         a_token_program: anchor_lang::solana_program::pubkey::Pubkey,
         signers: impl IntoIterator<Item = Keypair> + Send + 'static,
-    ) -> Result<EncodedConfirmedTransactionWithStatusMeta, ClientError> {
+    ) -> Result<TransactionOutcome, ClientError> {
         Ok(client
             .send_instruction(
                 PROGRAM_ID,
@@ -89,6 +122,32 @@ pub mod escrow_instruction {
             )
             .await?)
     }
+    pub async fn cancel_escrow_with_options(
+        client: &Client,
+        a_initializer: anchor_lang::solana_program::pubkey::Pubkey,
+        a_pda_deposit_token_account: anchor_lang::solana_program::pubkey::Pubkey,
+        a_pda_account: anchor_lang::solana_program::pubkey::Pubkey,
+        a_escrow_account: anchor_lang::solana_program::pubkey::Pubkey,
+        a_token_program: anchor_lang::solana_program::pubkey::Pubkey,
+        signers: impl IntoIterator<Item = Keypair> + Send + 'static,
+        options: &SendOptions,
+    ) -> Result<TransactionOutcome, ClientError> {
+        Ok(client
+            .send_instruction_with_options(
+                PROGRAM_ID,
+                escrow::instruction::CancelEscrow {},
+                escrow::accounts::CancelEscrow {
+                    initializer: a_initializer,
+                    pda_deposit_token_account: a_pda_deposit_token_account,
+                    pda_account: a_pda_account,
+                    escrow_account: a_escrow_account,
+                    token_program: a_token_program,
+                },
+                signers,
+                options,
+            )
+            .await?)
+    }
     pub fn cancel_escrow_ix(
         a_initializer: anchor_lang::solana_program::pubkey::Pubkey,
         a_pda_deposit_token_account: anchor_lang::solana_program::pubkey::Pubkey,
@@ -121,7 +180,7 @@ pub mod escrow_instruction {
         a_pda_account: anchor_lang::solana_program::pubkey::Pubkey,
         a_token_program: anchor_lang::solana_program::pubkey::Pubkey,
         signers: impl IntoIterator<Item = Keypair> + Send + 'static,
-    ) -> Result<EncodedConfirmedTransactionWithStatusMeta, ClientError> {
+    ) -> Result<TransactionOutcome, ClientError> {
         Ok(client
             .send_instruction(
                 PROGRAM_ID,
@@ -141,6 +200,40 @@ pub mod escrow_instruction {
             )
             .await?)
     }
+    pub async fn exchange_with_options(
+        client: &Client,
+        a_taker: anchor_lang::solana_program::pubkey::Pubkey,
+        a_taker_deposit_token_account: anchor_lang::solana_program::pubkey::Pubkey,
+        a_taker_receive_token_account: anchor_lang::solana_program::pubkey::Pubkey,
+        a_pda_deposit_token_account: anchor_lang::solana_program::pubkey::Pubkey,
+        a_initializer_receive_token_account: anchor_lang::solana_program::pubkey::Pubkey,
+        a_initializer_main_account: anchor_lang::solana_program::pubkey::Pubkey,
+        a_escrow_account: anchor_lang::solana_program::pubkey::Pubkey,
+        a_pda_account: anchor_lang::solana_program::pubkey::Pubkey,
+        a_token_program: anchor_lang::solana_program::pubkey::Pubkey,
+        signers: impl IntoIterator<Item = Keypair> + Send + 'static,
+        options: &SendOptions,
+    ) -> Result<TransactionOutcome, ClientError> {
+        Ok(client
+            .send_instruction_with_options(
+                PROGRAM_ID,
+                escrow::instruction::Exchange {},
+                escrow::accounts::Exchange {
+                    taker: a_taker,
+                    taker_deposit_token_account: a_taker_deposit_token_account,
+                    taker_receive_token_account: a_taker_receive_token_account,
+                    pda_deposit_token_account: a_pda_deposit_token_account,
+                    initializer_receive_token_account: a_initializer_receive_token_account,
+                    initializer_main_account: a_initializer_main_account,
+                    escrow_account: a_escrow_account,
+                    pda_account: a_pda_account,
+                    token_program: a_token_program,
+                },
+                signers,
+                options,
+            )
+            .await?)
+    }
     pub fn exchange_ix(
         a_taker: anchor_lang::solana_program::pubkey::Pubkey,
         a_taker_deposit_token_account: anchor_lang::solana_program::pubkey::Pubkey,
@@ -14,36 +14,49 @@ async fn init_fixture() -> Fixture {
     // Creation of token mint A
     fixture
         .client
-        .create_token_mint(&fixture.mint_a, fixture.mint_authority.pubkey(), None, 0)
+        .create_token_mint(
+            token::ID,
+            &fixture.mint_a,
+            fixture.mint_authority.pubkey(),
+            None,
+            0,
+        )
         .await?;
     // Creation of token mint B
     fixture
         .client
-        .create_token_mint(&fixture.mint_b, fixture.mint_authority.pubkey(), None, 0)
+        .create_token_mint(
+            token::ID,
+            &fixture.mint_b,
+            fixture.mint_authority.pubkey(),
+            None,
+            0,
+        )
         .await?;
     // Creation of alice's and bob's ATAs for token A
     fixture.alice_token_a_account = fixture
         .client
-        .create_associated_token_account(&fixture.alice_wallet, fixture.mint_a.pubkey())
+        .create_associated_token_account(token::ID, &fixture.alice_wallet, fixture.mint_a.pubkey())
         .await?;
     fixture.bob_token_a_account = fixture
         .client
-        .create_associated_token_account(&fixture.bob_wallet, fixture.mint_a.pubkey())
+        .create_associated_token_account(token::ID, &fixture.bob_wallet, fixture.mint_a.pubkey())
         .await?;
     // Creation of alice's and bob's ATAs for token B
     fixture.alice_token_b_account = fixture
         .client
-        .create_associated_token_account(&fixture.alice_wallet, fixture.mint_b.pubkey())
+        .create_associated_token_account(token::ID, &fixture.alice_wallet, fixture.mint_b.pubkey())
         .await?;
     fixture.bob_token_b_account = fixture
         .client
-        .create_associated_token_account(&fixture.bob_wallet, fixture.mint_b.pubkey())
+        .create_associated_token_account(token::ID, &fixture.bob_wallet, fixture.mint_b.pubkey())
         .await?;
 
     // Mint some tokens
     fixture
         .client
         .mint_tokens(
+            token::ID,
             fixture.mint_a.pubkey(),
             &fixture.mint_authority,
             fixture.alice_token_a_account,
@@ -53,6 +66,7 @@ async fn init_fixture() -> Fixture {
     fixture
         .client
         .mint_tokens(
+            token::ID,
             fixture.mint_b.pubkey(),
             &fixture.mint_authority,
             fixture.bob_token_b_account,
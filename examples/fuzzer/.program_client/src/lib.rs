@@ -12,7 +12,7 @@ pub mod fuzzer_instruction {
         a_user: anchor_lang::solana_program::pubkey::Pubkey,
         a_system_program: anchor_lang::solana_program::pubkey::Pubkey,
         signers: impl IntoIterator<Item = Keypair> + Send + 'static,
-    ) -> Result<EncodedConfirmedTransactionWithStatusMeta, ClientError> {
+    ) -> Result<TransactionOutcome, ClientError> {
         Ok(client
             .send_instruction(
                 PROGRAM_ID,
@@ -26,6 +26,28 @@ pub mod fuzzer_instruction {
             )
             .await?)
     }
+    pub async fn initialize_with_options(
+        client: &Client,
+        a_counter: anchor_lang::solana_program::pubkey::Pubkey,
+        a_user: anchor_lang::solana_program::pubkey::Pubkey,
+        a_system_program: anchor_lang::solana_program::pubkey::Pubkey,
+        signers: impl IntoIterator<Item = Keypair> + Send + 'static,
+        options: &SendOptions,
+    ) -> Result<TransactionOutcome, ClientError> {
+        Ok(client
+            .send_instruction_with_options(
+                PROGRAM_ID,
+                fuzzer::instruction::Initialize {},
+                fuzzer::accounts::Initialize {
+                    counter: a_counter,
+                    user: a_user,
+                    system_program: a_system_program,
+                },
+                signers,
+                options,
+            )
+            .await?)
+    }
     pub fn initialize_ix(
         a_counter: anchor_lang::solana_program::pubkey::Pubkey,
         a_user: anchor_lang::solana_program::pubkey::Pubkey,
@@ -49,7 +71,7 @@ pub mod fuzzer_instruction {
         a_counter: anchor_lang::solana_program::pubkey::Pubkey,
         a_authority: anchor_lang::solana_program::pubkey::Pubkey,
         signers: impl IntoIterator<Item = Keypair> + Send + 'static,
-    ) -> Result<EncodedConfirmedTransactionWithStatusMeta, ClientError> {
+    ) -> Result<TransactionOutcome, ClientError> {
         Ok(client
             .send_instruction(
                 PROGRAM_ID,
@@ -65,6 +87,31 @@ pub mod fuzzer_instruction {
             )
             .await?)
     }
+    pub async fn update_with_options(
+        client: &Client,
+        i_input1: u8,
+        i_input2: u8,
+        a_counter: anchor_lang::solana_program::pubkey::Pubkey,
+        a_authority: anchor_lang::solana_program::pubkey::Pubkey,
+        signers: impl IntoIterator<Item = Keypair> + Send + 'static,
+        options: &SendOptions,
+    ) -> Result<TransactionOutcome, ClientError> {
+        Ok(client
+            .send_instruction_with_options(
+                PROGRAM_ID,
+                fuzzer::instruction::Update {
+                    input1: i_input1,
+                    input2: i_input2,
+                },
+                fuzzer::accounts::Update {
+                    counter: a_counter,
+                    authority: a_authority,
+                },
+                signers,
+                options,
+            )
+            .await?)
+    }
     pub fn update_ix(
         i_input1: u8,
         i_input2: u8,
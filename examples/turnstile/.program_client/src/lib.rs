@@ -12,7 +12,7 @@ pub mod turnstile_instruction {
         a_user: anchor_lang::solana_program::pubkey::Pubkey,
         a_system_program: anchor_lang::solana_program::pubkey::Pubkey,
         signers: impl IntoIterator<Item = Keypair> + Send + 'static,
-    ) -> Result<EncodedConfirmedTransactionWithStatusMeta, ClientError> {
+    ) -> Result<TransactionOutcome, ClientError> {
         Ok(client
             .send_instruction(
                 PROGRAM_ID,
@@ -26,6 +26,28 @@ pub mod turnstile_instruction {
             )
             .await?)
     }
+    pub async fn initialize_with_options(
+        client: &Client,
+        a_state: anchor_lang::solana_program::pubkey::Pubkey,
+        a_user: anchor_lang::solana_program::pubkey::Pubkey,
+        a_system_program: anchor_lang::solana_program::pubkey::Pubkey,
+        signers: impl IntoIterator<Item = Keypair> + Send + 'static,
+        options: &SendOptions,
+    ) -> Result<TransactionOutcome, ClientError> {
+        Ok(client
+            .send_instruction_with_options(
+                PROGRAM_ID,
+                turnstile::instruction::Initialize {},
+                turnstile::accounts::Initialize {
+                    state: a_state,
+                    user: a_user,
+                    system_program: a_system_program,
+                },
+                signers,
+                options,
+            )
+            .await?)
+    }
     pub fn initialize_ix(
         a_state: anchor_lang::solana_program::pubkey::Pubkey,
         a_user: anchor_lang::solana_program::pubkey::Pubkey,
@@ -47,7 +69,7 @@ pub mod turnstile_instruction {
         i_dummy_arg: String,
         a_state: anchor_lang::solana_program::pubkey::Pubkey,
         signers: impl IntoIterator<Item = Keypair> + Send + 'static,
-    ) -> Result<EncodedConfirmedTransactionWithStatusMeta, ClientError> {
+    ) -> Result<TransactionOutcome, ClientError> {
         Ok(client
             .send_instruction(
                 PROGRAM_ID,
@@ -59,6 +81,25 @@ pub mod turnstile_instruction {
             )
             .await?)
     }
+    pub async fn coin_with_options(
+        client: &Client,
+        i_dummy_arg: String,
+        a_state: anchor_lang::solana_program::pubkey::Pubkey,
+        signers: impl IntoIterator<Item = Keypair> + Send + 'static,
+        options: &SendOptions,
+    ) -> Result<TransactionOutcome, ClientError> {
+        Ok(client
+            .send_instruction_with_options(
+                PROGRAM_ID,
+                turnstile::instruction::Coin {
+                    dummy_arg: i_dummy_arg,
+                },
+                turnstile::accounts::UpdateState { state: a_state },
+                signers,
+                options,
+            )
+            .await?)
+    }
     pub fn coin_ix(
         i_dummy_arg: String,
         a_state: anchor_lang::solana_program::pubkey::Pubkey,
@@ -76,7 +117,7 @@ pub mod turnstile_instruction {
         client: &Client,
         a_state: anchor_lang::solana_program::pubkey::Pubkey,
         signers: impl IntoIterator<Item = Keypair> + Send + 'static,
-    ) -> Result<EncodedConfirmedTransactionWithStatusMeta, ClientError> {
+    ) -> Result<TransactionOutcome, ClientError> {
         Ok(client
             .send_instruction(
                 PROGRAM_ID,
@@ -86,6 +127,22 @@ pub mod turnstile_instruction {
             )
             .await?)
     }
+    pub async fn push_with_options(
+        client: &Client,
+        a_state: anchor_lang::solana_program::pubkey::Pubkey,
+        signers: impl IntoIterator<Item = Keypair> + Send + 'static,
+        options: &SendOptions,
+    ) -> Result<TransactionOutcome, ClientError> {
+        Ok(client
+            .send_instruction_with_options(
+                PROGRAM_ID,
+                turnstile::instruction::Push {},
+                turnstile::accounts::UpdateState { state: a_state },
+                signers,
+                options,
+            )
+            .await?)
+    }
     pub fn push_ix(a_state: anchor_lang::solana_program::pubkey::Pubkey) -> Instruction {
         Instruction {
             program_id: PROGRAM_ID,
@@ -1,6 +1,6 @@
 use fehler::throws;
 use program_client::turnstile_instruction;
-use trdelnik_client::{anyhow::Result, *};
+use trdelnik_client::{anyhow::Result, solana_sdk::account::Account, *};
 
 #[throws]
 #[fixture]
@@ -75,6 +75,34 @@ async fn test_unhappy_path(#[future] init_fixture: Result<Fixture>) {
     assert!(!state.res);
 }
 
+/// The unlocked turnstile state created in the genesis instead of by the `initialize` instruction.
+#[throws]
+fn genesis_accounts() -> Vec<(Pubkey, Account)> {
+    let state = turnstile::State {
+        locked: false,
+        res: false,
+    };
+    vec![(
+        keypair(43).pubkey(),
+        Client::anchor_account(turnstile::ID, &state)?,
+    )]
+}
+
+#[trdelnik_test(accounts = "genesis_accounts")]
+async fn test_genesis_state() {
+    let client = Client::new(system_keypair(0));
+    client
+        .deploy_by_name(&program_keypair(1), "turnstile")
+        .await?;
+
+    // pushing the unlocked turnstile from the genesis
+    turnstile_instruction::push(&client, keypair(43).pubkey(), None).await?;
+
+    let state: turnstile::State = client.account_data(keypair(43).pubkey()).await?;
+    assert!(state.locked);
+    assert!(state.res);
+}
+
 struct Fixture {
     client: Client,
     program: Keypair,